@@ -0,0 +1,45 @@
+//! Lexes generated Monkey programs of increasing size and reports throughput.
+//! Lexing is linear in the input length, so the time per megabyte should stay
+//! roughly constant as the input grows.
+//!
+//! Run with `cargo bench --bench lexer`.
+
+extern crate writing_an_interpreter_in_rust;
+
+use std::time::Instant;
+use writing_an_interpreter_in_rust::lexer::Lexer;
+
+const SNIPPET: &str = "let add = fn(x, y) { x + y; };
+let result = add(five, ten) * (2 - 1) / 3;
+if (result != 10) { return true; } else { return !false; }
+";
+
+fn program(bytes: usize) -> String {
+    let mut s = String::with_capacity(bytes + SNIPPET.len());
+    while s.len() < bytes {
+        s.push_str(SNIPPET);
+    }
+    s
+}
+
+fn lex(input: &str) -> usize {
+    Lexer::new(input).count()
+}
+
+fn main() {
+    println!("{:>8} {:>12} {:>12} {:>12}", "size", "tokens", "total", "per MB");
+
+    for megabytes in &[1, 2, 4, 8] {
+        let input = program(megabytes * 1024 * 1024);
+
+        let start = Instant::now();
+        let tokens = lex(&input);
+        let elapsed = start.elapsed();
+
+        println!("{:>6}MB {:>12} {:>10.2}ms {:>10.2}ms",
+                 megabytes,
+                 tokens,
+                 elapsed.as_secs_f64() * 1000.0,
+                 elapsed.as_secs_f64() * 1000.0 / *megabytes as f64);
+    }
+}
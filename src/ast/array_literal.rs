@@ -0,0 +1,29 @@
+use std::fmt;
+use token::Span;
+use ast::Expression;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayLiteral {
+    pub elements: Vec<Expression>,
+    pub span: Span,
+}
+
+impl ArrayLiteral {
+    pub fn new(elements: Vec<Expression>, span: Span) -> Self {
+        ArrayLiteral {
+            elements,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for ArrayLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let elements = self.elements
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<String>>();
+
+        write!(f, "[{}]", elements.join(", "))
+    }
+}
@@ -0,0 +1,30 @@
+use std::fmt;
+use token::Span;
+use ast::{Expression, Identifier};
+
+/// A compound assignment such as `x += 1`. `operator` is the full operator,
+/// including the trailing `=`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignExpression {
+    pub name: Identifier,
+    pub operator: String,
+    pub value: Box<Expression>,
+    pub span: Span,
+}
+
+impl AssignExpression {
+    pub fn new(name: Identifier, operator: String, value: Expression, span: Span) -> Self {
+        AssignExpression {
+            name,
+            operator,
+            value: Box::new(value),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for AssignExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({} {} {})", self.name, self.operator, self.value)
+    }
+}
@@ -1,59 +1,131 @@
+use std::fmt;
+use token::Span;
+use ast::{LetStatement, ReturnStatement, ExpressionStatement, Identifier, IntegerLiteral,
+          FloatLiteral, StringLiteral, Boolean, PrefixExpression, InfixExpression,
+          AssignExpression, IfExpression, FunctionLiteral, CallExpression, ArrayLiteral,
+          IndexExpression, HashLiteral};
 
-pub trait Node {
-    fn token_literal(&self) -> String;
-    fn to_string(&self) -> String;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    pub statements: Vec<Statement>,
 }
 
-pub trait Statement: Node {
-    fn node(&self) -> String;
+impl Program {
+    pub fn new(statements: Vec<Statement>) -> Self {
+        Program { statements }
+    }
 }
 
-pub trait Expression: Node {
-    fn node(&self) -> String;
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for statement in &self.statements {
+            write!(f, "{}", statement)?;
+        }
+        Ok(())
+    }
 }
 
-pub struct Program {
-    pub statements: Vec<Box<Statement>>,
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Let(LetStatement),
+    Return(ReturnStatement),
+    Expression(ExpressionStatement),
 }
 
-impl Program {
-    pub fn new(statements: Vec<Box<Statement>>) -> Self {
-        Program { statements: statements }
+impl Statement {
+    pub fn span(&self) -> Span {
+        match *self {
+            Statement::Let(ref s) => s.span,
+            Statement::Return(ref s) => s.span,
+            Statement::Expression(ref s) => s.span,
+        }
     }
-    pub fn token_literal(&self) -> String {
-        if self.statements.len() > 0 {
-            for n in self.statements.iter() {
-                return n.token_literal();
-            }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Statement::Let(ref s) => s.fmt(f),
+            Statement::Return(ref s) => s.fmt(f),
+            Statement::Expression(ref s) => s.fmt(f),
         }
-        "".to_owned()
     }
+}
 
-    pub fn to_string(&self) -> String {
-        let mut s = String::new();
-        for statement in &self.statements {
-            s += statement.to_string().as_ref();
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Identifier(Identifier),
+    IntegerLiteral(IntegerLiteral),
+    FloatLiteral(FloatLiteral),
+    StringLiteral(StringLiteral),
+    Boolean(Boolean),
+    Prefix(PrefixExpression),
+    Infix(InfixExpression),
+    Assign(AssignExpression),
+    If(IfExpression),
+    FunctionLiteral(FunctionLiteral),
+    Call(CallExpression),
+    Array(ArrayLiteral),
+    Index(IndexExpression),
+    Hash(HashLiteral),
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match *self {
+            Expression::Identifier(ref e) => e.span,
+            Expression::IntegerLiteral(ref e) => e.span,
+            Expression::FloatLiteral(ref e) => e.span,
+            Expression::StringLiteral(ref e) => e.span,
+            Expression::Boolean(ref e) => e.span,
+            Expression::Prefix(ref e) => e.span,
+            Expression::Infix(ref e) => e.span,
+            Expression::Assign(ref e) => e.span,
+            Expression::If(ref e) => e.span,
+            Expression::FunctionLiteral(ref e) => e.span,
+            Expression::Call(ref e) => e.span,
+            Expression::Array(ref e) => e.span,
+            Expression::Index(ref e) => e.span,
+            Expression::Hash(ref e) => e.span,
         }
+    }
+}
 
-        s
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Expression::Identifier(ref e) => e.fmt(f),
+            Expression::IntegerLiteral(ref e) => e.fmt(f),
+            Expression::FloatLiteral(ref e) => e.fmt(f),
+            Expression::StringLiteral(ref e) => e.fmt(f),
+            Expression::Boolean(ref e) => e.fmt(f),
+            Expression::Prefix(ref e) => e.fmt(f),
+            Expression::Infix(ref e) => e.fmt(f),
+            Expression::Assign(ref e) => e.fmt(f),
+            Expression::If(ref e) => e.fmt(f),
+            Expression::FunctionLiteral(ref e) => e.fmt(f),
+            Expression::Call(ref e) => e.fmt(f),
+            Expression::Array(ref e) => e.fmt(f),
+            Expression::Index(ref e) => e.fmt(f),
+            Expression::Hash(ref e) => e.fmt(f),
+        }
     }
 }
 
 #[cfg(test)]
 pub mod test {
-    use ast::{Program, LetStatement, Identifier};
-    use token::{Token, TokenType};
+    use ast::{Program, Statement, Expression, LetStatement, Identifier};
+    use token::Span;
 
     #[test]
     fn test_to_stirng() {
-        let token = Token::new(TokenType::Let, "let".to_owned());
-        let identifier = Identifier::new(Token::new(TokenType::Ident, "myVar".to_owned()),
-                                         "myVar".to_owned());
-        let value = Identifier::new(Token::new(TokenType::Ident, "anotherVar".to_owned()),
-                                    "anotherVar".to_owned());
-
-        let let_statement = LetStatement::new(token, identifier, Some(Box::new(value)));
-        let program = Program { statements: vec![Box::new(let_statement)] };
+        let identifier = Identifier::new("myVar".to_owned(), Span::new(4, 9, 1, 5));
+        let value = Identifier::new("anotherVar".to_owned(), Span::new(12, 22, 1, 13));
+
+        let let_statement = LetStatement::new(identifier,
+                                              Expression::Identifier(value),
+                                              Span::new(0, 23, 1, 1));
+        let program = Program { statements: vec![Statement::Let(let_statement)] };
 
         assert_eq!(program.to_string(), "let myVar = anotherVar;");
     }
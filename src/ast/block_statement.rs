@@ -0,0 +1,28 @@
+use std::fmt;
+use token::Span;
+use ast::Statement;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockStatement {
+    pub statements: Vec<Statement>,
+    pub span: Span,
+}
+
+impl BlockStatement {
+    pub fn new(statements: Vec<Statement>, span: Span) -> Self {
+        BlockStatement {
+            statements,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for BlockStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{ ")?;
+        for statement in &self.statements {
+            write!(f, "{}", statement)?;
+        }
+        write!(f, " }}")
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+use token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Boolean {
+    pub value: bool,
+    pub span: Span,
+}
+
+impl Boolean {
+    pub fn new(value: bool, span: Span) -> Self {
+        Boolean {
+            value,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for Boolean {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt;
+use token::Span;
+use ast::Expression;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallExpression {
+    pub function: Box<Expression>,
+    pub arguments: Vec<Expression>,
+    pub span: Span,
+}
+
+impl CallExpression {
+    pub fn new(function: Expression, arguments: Vec<Expression>, span: Span) -> Self {
+        CallExpression {
+            function: Box::new(function),
+            arguments,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for CallExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let arguments = self.arguments
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<String>>();
+
+        write!(f, "{}({})", self.function, arguments.join(", "))
+    }
+}
@@ -1,27 +1,24 @@
-use token::Token;
-use ast::{Node, Statement, Expression};
+use std::fmt;
+use token::Span;
+use ast::Expression;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct ExpressionStatement {
-    pub token: Token,
-    pub expression: Option<Box<Expression>>,
+    pub expression: Expression,
+    pub span: Span,
 }
 
-impl Statement for ExpressionStatement {
-    fn node(&self) -> String {
-        "".to_owned()
+impl ExpressionStatement {
+    pub fn new(expression: Expression, span: Span) -> Self {
+        ExpressionStatement {
+            expression,
+            span,
+        }
     }
 }
 
-impl Node for ExpressionStatement {
-    fn token_literal(&self) -> String {
-        self.token.literal.clone()
+impl fmt::Display for ExpressionStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.expression.fmt(f)
     }
-
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-        if let Some(ref expression) = self.expression {
-            s += expression.to_string().as_ref();
-        }
-        s
-    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,24 @@
+use std::fmt;
+use token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatLiteral {
+    pub value: f64,
+    pub span: Span,
+}
+
+impl FloatLiteral {
+    pub fn new(value: f64, span: Span) -> Self {
+        FloatLiteral {
+            value,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for FloatLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // `{:?}` keeps the fraction of whole numbers, so `3.0` doesn't print as `3`.
+        write!(f, "{:?}", self.value)
+    }
+}
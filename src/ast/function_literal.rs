@@ -0,0 +1,32 @@
+use std::fmt;
+use std::rc::Rc;
+use token::Span;
+use ast::{Identifier, BlockStatement};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionLiteral {
+    pub parameters: Vec<Identifier>,
+    pub body: Rc<BlockStatement>,
+    pub span: Span,
+}
+
+impl FunctionLiteral {
+    pub fn new(parameters: Vec<Identifier>, body: BlockStatement, span: Span) -> Self {
+        FunctionLiteral {
+            parameters,
+            body: Rc::new(body),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for FunctionLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parameters = self.parameters
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>();
+
+        write!(f, "fn({}) {}", parameters.join(", "), self.body)
+    }
+}
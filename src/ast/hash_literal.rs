@@ -0,0 +1,29 @@
+use std::fmt;
+use token::Span;
+use ast::Expression;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashLiteral {
+    pub pairs: Vec<(Expression, Expression)>,
+    pub span: Span,
+}
+
+impl HashLiteral {
+    pub fn new(pairs: Vec<(Expression, Expression)>, span: Span) -> Self {
+        HashLiteral {
+            pairs,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for HashLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let pairs = self.pairs
+            .iter()
+            .map(|(key, value)| format!("{}: {}", key, value))
+            .collect::<Vec<String>>();
+
+        write!(f, "{{{}}}", pairs.join(", "))
+    }
+}
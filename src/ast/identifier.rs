@@ -1,33 +1,23 @@
-use token::Token;
-use ast::{Node, Expression};
+use std::fmt;
+use token::Span;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Identifier {
-    pub token: Token,
     pub value: String,
+    pub span: Span,
 }
 
 impl Identifier {
-    pub fn new(token: Token, value: String) -> Self {
+    pub fn new(value: String, span: Span) -> Self {
         Identifier {
-            token: token,
-            value: value,
+            value,
+            span,
         }
     }
 }
 
-impl Node for Identifier {
-    fn token_literal(&self) -> String {
-        self.token.literal.clone()
-    }
-
-    fn to_string(&self) -> String {
-        self.value.clone()
-    }
-}
-
-impl Expression for Identifier {
-    fn node(&self) -> String {
-        "".to_owned()
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
     }
 }
@@ -0,0 +1,36 @@
+use std::fmt;
+use token::Span;
+use ast::{Expression, BlockStatement};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IfExpression {
+    pub condition: Box<Expression>,
+    pub consequence: BlockStatement,
+    pub alternative: Option<BlockStatement>,
+    pub span: Span,
+}
+
+impl IfExpression {
+    pub fn new(condition: Expression,
+               consequence: BlockStatement,
+               alternative: Option<BlockStatement>,
+               span: Span)
+               -> Self {
+        IfExpression {
+            condition: Box::new(condition),
+            consequence,
+            alternative,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for IfExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "if {} {}", self.condition, self.consequence)?;
+        if let Some(ref alternative) = self.alternative {
+            write!(f, " else {}", alternative)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use std::fmt;
+use token::Span;
+use ast::Expression;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexExpression {
+    pub left: Box<Expression>,
+    pub index: Box<Expression>,
+    pub span: Span,
+}
+
+impl IndexExpression {
+    pub fn new(left: Expression, index: Expression, span: Span) -> Self {
+        IndexExpression {
+            left: Box::new(left),
+            index: Box::new(index),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for IndexExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}[{}])", self.left, self.index)
+    }
+}
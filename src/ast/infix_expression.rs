@@ -0,0 +1,28 @@
+use std::fmt;
+use token::Span;
+use ast::Expression;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfixExpression {
+    pub left: Box<Expression>,
+    pub operator: String,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+impl InfixExpression {
+    pub fn new(left: Expression, operator: String, right: Expression, span: Span) -> Self {
+        InfixExpression {
+            left: Box::new(left),
+            operator,
+            right: Box::new(right),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for InfixExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({} {} {})", self.left, self.operator, self.right)
+    }
+}
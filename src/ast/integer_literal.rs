@@ -0,0 +1,23 @@
+use std::fmt;
+use token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegerLiteral {
+    pub value: i64,
+    pub span: Span,
+}
+
+impl IntegerLiteral {
+    pub fn new(value: i64, span: Span) -> Self {
+        IntegerLiteral {
+            value,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for IntegerLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
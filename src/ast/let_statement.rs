@@ -1,40 +1,26 @@
-use token::Token;
-use ast::{Node, Statement, Identifier, Expression};
+use std::fmt;
+use token::Span;
+use ast::{Identifier, Expression};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct LetStatement {
-    pub token: Token,
     pub name: Identifier,
-    pub value: Option<Box<Expression>>,
+    pub value: Expression,
+    pub span: Span,
 }
 
 impl LetStatement {
-    pub fn new(token: Token, name: Identifier, value: Option<Box<Expression>>) -> Self {
+    pub fn new(name: Identifier, value: Expression, span: Span) -> Self {
         LetStatement {
-            token: token,
-            name: name,
-            value: value,
+            name,
+            value,
+            span,
         }
     }
 }
 
-impl Statement for LetStatement {
-    fn node(&self) -> String {
-        "".to_owned()
-    }
-}
-
-impl Node for LetStatement {
-    fn token_literal(&self) -> String {
-        self.token.literal.clone()
-    }
-
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-        s += format!("{} {} = ", self.token_literal(), self.name.to_string()).as_str();
-        if let Some(ref value) = self.value {
-            s += value.to_string().as_ref();
-        }
-        s += ";";
-        s
+impl fmt::Display for LetStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "let {} = {};", self.name, self.value)
     }
 }
@@ -3,9 +3,37 @@ mod identifier;
 mod let_statement;
 mod return_statement;
 mod expression_statement;
+mod block_statement;
+mod integer_literal;
+mod float_literal;
+mod string_literal;
+mod boolean;
+mod prefix_expression;
+mod infix_expression;
+mod assign_expression;
+mod if_expression;
+mod function_literal;
+mod call_expression;
+mod array_literal;
+mod index_expression;
+mod hash_literal;
 
 pub use self::ast::*;
 pub use self::identifier::*;
 pub use self::let_statement::*;
 pub use self::return_statement::*;
 pub use self::expression_statement::*;
+pub use self::block_statement::*;
+pub use self::integer_literal::*;
+pub use self::float_literal::*;
+pub use self::string_literal::*;
+pub use self::boolean::*;
+pub use self::prefix_expression::*;
+pub use self::infix_expression::*;
+pub use self::assign_expression::*;
+pub use self::if_expression::*;
+pub use self::function_literal::*;
+pub use self::call_expression::*;
+pub use self::array_literal::*;
+pub use self::index_expression::*;
+pub use self::hash_literal::*;
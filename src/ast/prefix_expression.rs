@@ -0,0 +1,26 @@
+use std::fmt;
+use token::Span;
+use ast::Expression;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrefixExpression {
+    pub operator: String,
+    pub right: Box<Expression>,
+    pub span: Span,
+}
+
+impl PrefixExpression {
+    pub fn new(operator: String, right: Expression, span: Span) -> Self {
+        PrefixExpression {
+            operator,
+            right: Box::new(right),
+            span,
+        }
+    }
+}
+
+impl fmt::Display for PrefixExpression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}{})", self.operator, self.right)
+    }
+}
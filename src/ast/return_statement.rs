@@ -1,29 +1,24 @@
-use token::Token;
-use ast::{Node, Statement, Expression};
+use std::fmt;
+use token::Span;
+use ast::Expression;
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct ReturnStatement {
-    pub token: Token,
-    pub return_value: Option<Box<Expression>>,
+    pub return_value: Expression,
+    pub span: Span,
 }
 
-impl Statement for ReturnStatement {
-    fn node(&self) -> String {
-        "".to_owned()
+impl ReturnStatement {
+    pub fn new(return_value: Expression, span: Span) -> Self {
+        ReturnStatement {
+            return_value,
+            span,
+        }
     }
 }
 
-impl Node for ReturnStatement {
-    fn token_literal(&self) -> String {
-        self.token.literal.clone()
-    }
-
-    fn to_string(&self) -> String {
-        let mut s = String::new();
-        s = s + self.token_literal().as_str();
-        if let Some(ref expression) = self.return_value {
-            s = s + expression.to_string().as_ref();
-        }
-        s += ";";
-        s
+impl fmt::Display for ReturnStatement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "return {};", self.return_value)
     }
 }
@@ -0,0 +1,23 @@
+use std::fmt;
+use token::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringLiteral {
+    pub value: String,
+    pub span: Span,
+}
+
+impl StringLiteral {
+    pub fn new(value: String, span: Span) -> Self {
+        StringLiteral {
+            value,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for StringLiteral {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.value)
+    }
+}
@@ -0,0 +1,286 @@
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, IsTerminal, Read};
+use std::panic;
+use std::rc::Rc;
+use std::thread;
+use lexer::Lexer;
+use parser::Parser;
+use evaluator::{eval_with_context, Environment, Context, Limits, Object};
+use diagnostics::{Diagnostic, Renderer};
+use repl;
+
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_RUNTIME_ERROR: i32 = 1;
+pub const EXIT_PARSE_ERROR: i32 = 2;
+pub const EXIT_USAGE: i32 = 64;
+pub const EXIT_NO_INPUT: i32 = 66;
+
+/// Scripts and the REPL run on a thread of their own with this much stack.
+/// Only the pages actually used are committed.
+pub const STACK_SIZE: usize = 256 << 20;
+
+/// The default limits, sized for `STACK_SIZE`: in a debug build a level of
+/// nesting takes up to about 4 KB of stack, and a call about 4 KB on top of
+/// that. Hitting these limits is a runtime error instead of a crash.
+pub const LIMITS: Limits = Limits {
+    max_steps: None,
+    max_call_depth: Some(10_000),
+    max_nesting_depth: Some(40_000),
+    max_collection_size: None,
+};
+
+const USAGE: &str = "Usage:
+    monkey [options]                 start the interactive REPL
+    monkey [options] run <file>      run a Monkey script (`-` reads from stdin)
+    monkey [options] -               run a Monkey script read from stdin
+    monkey [options] -e <source>     evaluate <source> and print the result
+    monkey -h, --help                show this message
+
+Options:
+    --max-call-depth <n>       how deeply function calls may nest
+    --max-nesting-depth <n>    how deeply expressions may nest during evaluation";
+
+/// A parsed command line: what to run, and the limits to run it under.
+#[derive(Debug, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub limits: Limits,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Repl,
+    Run(Input),
+    Eval(String),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Input {
+    File(String),
+    Stdin,
+}
+
+impl Input {
+    fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            Input::Stdin
+        } else {
+            Input::File(arg.to_owned())
+        }
+    }
+
+    fn name(&self) -> &str {
+        match *self {
+            Input::File(ref path) => path,
+            Input::Stdin => "<stdin>",
+        }
+    }
+
+    fn read(&self) -> io::Result<String> {
+        let mut source = String::new();
+        match *self {
+            Input::File(ref path) => File::open(path)?.read_to_string(&mut source)?,
+            Input::Stdin => io::stdin().read_to_string(&mut source)?,
+        };
+        Ok(source)
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let args = args.iter().map(|a| a.as_str()).collect::<Vec<&str>>();
+    let mut limits = LIMITS;
+    let mut rest = args.as_slice();
+
+    loop {
+        rest = match rest {
+            ["--max-call-depth", value, rest @ ..] => {
+                limits.max_call_depth = Some(parse_limit("--max-call-depth", value)?);
+                rest
+            }
+            ["--max-nesting-depth", value, rest @ ..] => {
+                limits.max_nesting_depth = Some(parse_limit("--max-nesting-depth", value)?);
+                rest
+            }
+            ["--max-call-depth"] | ["--max-nesting-depth"] => {
+                return Err(format!("`{}` requires a number", rest[0]));
+            }
+            _ => break,
+        };
+    }
+
+    let command = match rest {
+        [] => Command::Repl,
+        ["-h"] | ["--help"] => Command::Help,
+        ["run", path] => Command::Run(Input::from_arg(path)),
+        ["-"] => Command::Run(Input::Stdin),
+        ["-e", source] => Command::Eval((*source).to_owned()),
+        ["run"] => return Err("`run` requires a file argument".to_owned()),
+        ["-e"] => return Err("`-e` requires a source argument".to_owned()),
+        _ => return Err(format!("unexpected arguments: {}", rest.join(" "))),
+    };
+    Ok(Options { command, limits })
+}
+
+fn parse_limit(flag: &str, value: &str) -> Result<usize, String> {
+    value.parse().map_err(|_| format!("`{}` requires a number, got `{}`", flag, value))
+}
+
+/// Runs the command line `args` (without the program name) and returns the
+/// process exit code.
+pub fn run(args: &[String]) -> i32 {
+    let Options { command, limits } = match parse_args(args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    match command {
+        Command::Repl => {
+            with_large_stack(|| repl::start(limits));
+            EXIT_SUCCESS
+        }
+        Command::Help => {
+            println!("{}", USAGE);
+            EXIT_SUCCESS
+        }
+        Command::Eval(source) => run_source(&source, "<expr>", limits),
+        Command::Run(input) => {
+            match input.read() {
+                Ok(source) => run_source(&source, input.name(), limits),
+                Err(err) => {
+                    eprintln!("error: couldn't read {}: {}", input.name(), err);
+                    EXIT_NO_INPUT
+                }
+            }
+        }
+    }
+}
+
+/// Parses and evaluates `source` under `limits`, printing the result (unless
+/// it is `null`) to stdout and any diagnostics to stderr.
+pub fn run_source(source: &str, file_name: &str, limits: Limits) -> i32 {
+    with_large_stack(|| {
+        let renderer = if io::stderr().is_terminal() {
+            Renderer::colored()
+        } else {
+            Renderer::plain()
+        };
+
+        let mut parser = Parser::new(Lexer::new(source));
+        let program = match parser.parser_program() {
+            Ok(program) => program,
+            Err(errors) => {
+                for err in errors {
+                    eprint!("{}", renderer.render(&Diagnostic::from(&err), file_name, source));
+                }
+                return EXIT_PARSE_ERROR;
+            }
+        };
+
+        let env = Rc::new(RefCell::new(Environment::new()));
+        match eval_with_context(&program, &env, &Context::new(limits)) {
+            Object::Error(err) => {
+                eprint!("{}", renderer.render(&Diagnostic::from(&err), file_name, source));
+                EXIT_RUNTIME_ERROR
+            }
+            Object::Null => EXIT_SUCCESS,
+            result => {
+                println!("{}", result);
+                EXIT_SUCCESS
+            }
+        }
+    })
+}
+
+/// Runs `f` on a thread with `STACK_SIZE` bytes of stack and returns its
+/// result.
+fn with_large_stack<T, F>(f: F) -> T
+    where T: Send,
+          F: FnOnce() -> T + Send
+{
+    thread::scope(|scope| {
+        let handle = thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("couldn't spawn the evaluator thread");
+        handle.join().unwrap_or_else(|payload| panic::resume_unwind(payload))
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use evaluator::Limits;
+    use cli::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let tests = vec![(vec![], Command::Repl),
+                         (vec!["--help"], Command::Help),
+                         (vec!["run", "script.mk"],
+                          Command::Run(Input::File("script.mk".to_owned()))),
+                         (vec!["run", "-"], Command::Run(Input::Stdin)),
+                         (vec!["-"], Command::Run(Input::Stdin)),
+                         (vec!["-e", "1 + 2"], Command::Eval("1 + 2".to_owned()))];
+
+        for (input, expected) in tests {
+            let expected = Options {
+                command: expected,
+                limits: LIMITS,
+            };
+            assert_eq!(Ok(expected), parse_args(&args(&input)));
+        }
+
+        let options = parse_args(&args(&["--max-call-depth",
+                                         "50",
+                                         "--max-nesting-depth",
+                                         "100",
+                                         "run",
+                                         "script.mk"]))
+            .unwrap();
+        assert_eq!(Command::Run(Input::File("script.mk".to_owned())), options.command);
+        assert_eq!(Limits {
+                       max_call_depth: Some(50),
+                       max_nesting_depth: Some(100),
+                       ..LIMITS
+                   },
+                   options.limits);
+
+        assert!(parse_args(&args(&["run"])).is_err());
+        assert!(parse_args(&args(&["-e"])).is_err());
+        assert!(parse_args(&args(&["script.mk", "extra"])).is_err());
+        assert!(parse_args(&args(&["--max-call-depth"])).is_err());
+        assert!(parse_args(&args(&["--max-call-depth", "deep", "-e", "1"])).is_err());
+        assert!(parse_args(&args(&["run", "script.mk", "--max-call-depth", "5"])).is_err());
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let sum = "let sum = fn(n) { if (n == 0) { 0 } else { n + sum(n - 1) } };";
+        let tests = vec![("let x = 1; x * 2".to_owned(), EXIT_SUCCESS),
+                         ("let x = ;".to_owned(), EXIT_PARSE_ERROR),
+                         ("1 + true".to_owned(), EXIT_RUNTIME_ERROR),
+                         (format!("{} sum(3000)", sum), EXIT_SUCCESS),
+                         (format!("1{}", " + 1".repeat(5000)), EXIT_SUCCESS),
+                         (format!("1{}", " + 1".repeat(100_000)), EXIT_PARSE_ERROR),
+                         ("let f = fn(n) { f(n + 1) }; f(0)".to_owned(), EXIT_RUNTIME_ERROR)];
+
+        for (source, expected) in tests {
+            assert_eq!(expected, run_source(&source, "<test>", LIMITS));
+        }
+
+        let limits = Limits { max_call_depth: Some(100), ..LIMITS };
+        assert_eq!(EXIT_RUNTIME_ERROR,
+                   run_source(&format!("{} sum(300)", sum), "<test>", limits));
+
+        assert_eq!(EXIT_NO_INPUT, run(&args(&["run", "does/not/exist.mk"])));
+        assert_eq!(EXIT_USAGE, run(&args(&["--unknown"])));
+    }
+}
@@ -0,0 +1,3 @@
+mod cli;
+
+pub use self::cli::*;
@@ -0,0 +1,110 @@
+use token::{Span, TokenType};
+use parser::{ParseError, ParseErrorKind};
+use lexer::{LexError, LexErrorKind};
+use evaluator::RuntimeError;
+
+/// A located, user-facing description of a problem in Monkey source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub code: &'static str,
+    pub message: String,
+    pub span: Option<Span>,
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn error(code: &'static str, message: String, span: Option<Span>) -> Self {
+        Diagnostic {
+            code,
+            message,
+            span,
+            help: vec![],
+        }
+    }
+
+    pub fn with_help(mut self, help: &str) -> Self {
+        self.help.push(help.to_owned());
+        self
+    }
+}
+
+impl<'a> From<&'a ParseError> for Diagnostic {
+    fn from(err: &'a ParseError) -> Self {
+        let span = Some(err.span());
+        let message = err.error_description();
+
+        match *err.kind() {
+            ParseErrorKind::NoPrefixParseFn { token_type: TokenType::Illegal, .. } => {
+                Diagnostic::error("E0006", "illegal character".to_owned(), span)
+                    .with_help("this character is not part of the Monkey language")
+            }
+            ParseErrorKind::UnexpectedToken { .. } => Diagnostic::error("E0001", message, span),
+            ParseErrorKind::NoPrefixParseFn { token_type: TokenType::EOF, .. } => {
+                Diagnostic::error("E0004",
+                                  "unexpected end of input, expected an expression".to_owned(),
+                                  span)
+            }
+            ParseErrorKind::NoPrefixParseFn { .. } => {
+                Diagnostic::error("E0002", message, span)
+                    .with_help("an expression can't start with this token")
+            }
+            ParseErrorKind::IntegerOverflow { .. } => {
+                Diagnostic::error("E0003", message, span)
+                    .with_help("the largest integer is 9223372036854775807; use a float for \
+                                larger magnitudes")
+            }
+            ParseErrorKind::MalformedNumber { .. } => {
+                Diagnostic::error("E0009", message, span)
+                    .with_help("numbers look like 42, 1_000, 3.14, 1e-9, 0xFF, 0o755 or 0b1010")
+            }
+            ParseErrorKind::UnexpectedEof { .. } => {
+                Diagnostic::error("E0004", message, span)
+                    .with_help("the input ended before this construct was closed")
+            }
+            ParseErrorKind::UnterminatedString { .. } => {
+                Diagnostic::error("E0005", message, span)
+                    .with_help("add a closing `\"` to end the string")
+            }
+            ParseErrorKind::UnterminatedComment { .. } => unterminated_comment(span),
+            ParseErrorKind::InvalidEscape { .. } => {
+                Diagnostic::error("E0007", message, span)
+                    .with_help("valid escapes are \\n, \\t, \\\", \\\\ and \\u{...}")
+            }
+            ParseErrorKind::InvalidAssignmentTarget { .. } => {
+                Diagnostic::error("E0010", message, span)
+                    .with_help("only a variable can be assigned to")
+            }
+            ParseErrorKind::NestingTooDeep { max, .. } => {
+                let help = format!("expressions can nest at most {} levels deep; split this one \
+                                    up with `let`",
+                                   max);
+                Diagnostic::error("E0011", message, span).with_help(&help)
+            }
+        }
+    }
+}
+
+impl<'a> From<&'a LexError> for Diagnostic {
+    fn from(err: &'a LexError) -> Self {
+        match err.kind {
+            LexErrorKind::IllegalCharacter => {
+                Diagnostic::error("E0006", "illegal character".to_owned(), Some(err.span))
+                    .with_help("this character is not part of the Monkey language")
+            }
+            LexErrorKind::UnterminatedComment => unterminated_comment(Some(err.span)),
+        }
+    }
+}
+
+impl<'a> From<&'a RuntimeError> for Diagnostic {
+    fn from(err: &'a RuntimeError) -> Self {
+        Diagnostic::error("E0100", err.to_string(), err.span)
+    }
+}
+
+fn unterminated_comment(span: Option<Span>) -> Diagnostic {
+    // Point at the opening `/*` rather than the rest of the input.
+    let span = span.map(|span| Span::new(span.start, span.start + 2, span.line, span.column));
+    Diagnostic::error("E0008", "unterminated block comment".to_owned(), span)
+        .with_help("add a closing `*/`; block comments nest, so each `/*` needs one")
+}
@@ -0,0 +1,5 @@
+mod diagnostic;
+mod renderer;
+
+pub use self::diagnostic::*;
+pub use self::renderer::*;
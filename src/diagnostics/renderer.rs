@@ -0,0 +1,242 @@
+use token::Span;
+use diagnostics::Diagnostic;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const BOLD_RED: &str = "\x1b[1;31m";
+const BOLD_BLUE: &str = "\x1b[1;34m";
+
+/// Formats diagnostics in the style of rustc: a header with the error code,
+/// the location, the offending source line and a caret underline.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Renderer {
+    color: bool,
+}
+
+impl Renderer {
+    pub fn plain() -> Self {
+        Renderer { color: false }
+    }
+
+    pub fn colored() -> Self {
+        Renderer { color: true }
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic, file_name: &str, source: &str) -> String {
+        let mut s = format!("{}{}\n",
+                            self.paint(BOLD_RED, &format!("error[{}]", diagnostic.code)),
+                            self.paint(BOLD, &format!(": {}", diagnostic.message)));
+
+        // A span that doesn't point into `source`, such as a REPL error raised by
+        // a function defined on an earlier line, is rendered as if there were none.
+        let span = match diagnostic.span.filter(|span| span_fits(span, source)) {
+            Some(span) => span,
+            None => {
+                s += &format!("{} {}\n", self.paint(BOLD_BLUE, "-->"), file_name);
+                for help in &diagnostic.help {
+                    s += &format!("{} help: {}\n", self.paint(BOLD_BLUE, "="), help);
+                }
+                return s;
+            }
+        };
+
+        let line_number = span.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let line = source.lines().nth(span.line - 1).unwrap_or("");
+
+        // Spans covering several lines are underlined up to the end of the first one.
+        let start = span.start;
+        let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+        let end = span.end.min(line_start + line.len()).max(start);
+        let width = source[start..end].chars().count().max(1);
+        // Keep tabs so that the caret lines up however wide the terminal shows them.
+        let padding = source[line_start..start]
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+
+        s += &format!("{}{} {}:{}:{}\n",
+                      gutter,
+                      self.paint(BOLD_BLUE, "-->"),
+                      file_name,
+                      span.line,
+                      span.column);
+        s += &format!("{} {}\n", gutter, self.paint(BOLD_BLUE, "|"));
+        s += &format!("{} {} {}\n",
+                      self.paint(BOLD_BLUE, &line_number),
+                      self.paint(BOLD_BLUE, "|"),
+                      line);
+        s += &format!("{} {} {}{}\n",
+                      gutter,
+                      self.paint(BOLD_BLUE, "|"),
+                      padding,
+                      self.paint(BOLD_RED, &"^".repeat(width)));
+
+        for help in &diagnostic.help {
+            s += &format!("{} {} help: {}\n", gutter, self.paint(BOLD_BLUE, "="), help);
+        }
+
+        s
+    }
+
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_owned()
+        }
+    }
+}
+
+/// Whether `span` lies within `source` and starts on the line it claims to.
+fn span_fits(span: &Span, source: &str) -> bool {
+    span.start <= span.end && span.end <= source.len() && source.is_char_boundary(span.start) &&
+    source.is_char_boundary(span.end) &&
+    source[..span.start].matches('\n').count() + 1 == span.line
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use lexer::Lexer;
+    use parser::Parser;
+    use token::Span;
+    use evaluator::{eval, Environment, Object};
+    use diagnostics::{Diagnostic, Renderer};
+
+    fn parse_diagnostics(input: &str) -> Vec<Diagnostic> {
+        let mut parser = Parser::new(Lexer::new(input));
+        let errors = parser.parser_program().err().unwrap();
+        errors.iter().map(Diagnostic::from).collect()
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let input = "let x = 5;\nlet y = (x + ;\n";
+        let diagnostics = parse_diagnostics(input);
+
+        let expected = r#"error[E0002]: no prefix parse function for Semicolon found
+ --> main.mk:2:14
+  |
+2 | let y = (x + ;
+  |              ^
+  = help: an expression can't start with this token
+"#;
+        assert_eq!(expected, Renderer::plain().render(&diagnostics[0], "main.mk", input));
+    }
+
+    #[test]
+    fn test_render_underlines_span() {
+        let input = "let a = 1;\nlet b = a + true;";
+        let diagnostic = Diagnostic::error("E0100",
+                                           "type mismatch: INTEGER + BOOLEAN".to_owned(),
+                                           Some(Span::new(19, 27, 2, 9)));
+
+        let expected = r#"error[E0100]: type mismatch: INTEGER + BOOLEAN
+ --> main.mk:2:9
+  |
+2 | let b = a + true;
+  |         ^^^^^^^^
+"#;
+        assert_eq!(expected, Renderer::plain().render(&diagnostic, "main.mk", input));
+    }
+
+    #[test]
+    fn test_render_without_span() {
+        let diagnostic = Diagnostic::error("E0100", "stack overflow".to_owned(), None)
+            .with_help("check for unbounded recursion");
+
+        let expected = "error[E0100]: stack overflow\n--> main.mk\n= help: check for \
+                        unbounded recursion\n";
+        assert_eq!(expected, Renderer::plain().render(&diagnostic, "main.mk", ""));
+    }
+
+    #[test]
+    fn test_render_string_errors() {
+        let input = "let s = \"a\\qb\";\nlet t = \"oops;\n";
+        let diagnostics = parse_diagnostics(input);
+
+        let expected = r#"error[E0007]: invalid escape sequence \q
+ --> main.mk:1:11
+  |
+1 | let s = "a\qb";
+  |           ^^
+  = help: valid escapes are \n, \t, \", \\ and \u{...}
+"#;
+        assert_eq!(expected, Renderer::plain().render(&diagnostics[0], "main.mk", input));
+
+        let expected = r#"error[E0005]: unterminated string literal
+ --> main.mk:2:9
+  |
+2 | let t = "oops;
+  |         ^
+  = help: add a closing `"` to end the string
+"#;
+        assert_eq!(expected, Renderer::plain().render(&diagnostics[1], "main.mk", input));
+    }
+
+    #[test]
+    fn test_render_unterminated_comment() {
+        let input = "let x = 1; /* start\n/* nested */\nx";
+        let diagnostics = parse_diagnostics(input);
+
+        let expected = r#"error[E0008]: unterminated block comment
+ --> main.mk:1:12
+  |
+1 | let x = 1; /* start
+  |            ^^
+  = help: add a closing `*/`; block comments nest, so each `/*` needs one
+"#;
+        assert_eq!(expected, Renderer::plain().render(&diagnostics[0], "main.mk", input));
+    }
+
+    #[test]
+    fn test_render_tab_indented_line() {
+        let input = "if (true) {\n\t\t1 + true\n}";
+        let diagnostic = Diagnostic::error("E0100",
+                                           "type mismatch: INTEGER + BOOLEAN".to_owned(),
+                                           Some(Span::new(14, 22, 2, 3)));
+
+        let expected = "error[E0100]: type mismatch: INTEGER + BOOLEAN\n --> main.mk:2:3\n  |\n2 \
+                        | \t\t1 + true\n  | \t\t^^^^^^^^\n";
+        assert_eq!(expected, Renderer::plain().render(&diagnostic, "main.mk", input));
+    }
+
+    #[test]
+    fn test_render_span_from_other_source() {
+        // In the REPL every line is a separate source, but a function defined on
+        // an earlier line reports errors with spans into that line.
+        let env = Rc::new(RefCell::new(Environment::new()));
+        let mut result = Object::Null;
+        for input in &["let f = fn() { -true };", "f()"] {
+            let program = Parser::new(Lexer::new(input)).parser_program().unwrap();
+            result = eval(&program, &env);
+        }
+
+        let err = match result {
+            Object::Error(err) => err,
+            other => panic!("no error object returned. got={}", other),
+        };
+        let expected = "error[E0100]: unknown operator: -BOOLEAN\n--> <stdin>\n";
+        assert_eq!(expected,
+                   Renderer::plain().render(&Diagnostic::from(&err), "<stdin>", "f()"));
+
+        let diagnostic = Diagnostic::error("E0100",
+                                           "oops".to_owned(),
+                                           Some(Span::new(2, 3, 2, 3)));
+        assert_eq!("error[E0100]: oops\n--> <stdin>\n",
+                   Renderer::plain().render(&diagnostic, "<stdin>", "f()"));
+    }
+
+    #[test]
+    fn test_render_colored() {
+        let input = "1 + $";
+        let diagnostics = parse_diagnostics(input);
+        let rendered = Renderer::colored().render(&diagnostics[0], "<stdin>", input);
+
+        assert!(rendered.starts_with("\x1b[1;31merror[E0006]\x1b[0m\x1b[1m: illegal character"));
+        assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+        assert!(rendered.contains("help: this character is not part of the Monkey language"));
+    }
+}
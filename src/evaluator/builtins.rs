@@ -0,0 +1,320 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::rc::Rc;
+use evaluator::{Object, HashKey, Context, RuntimeError};
+
+/// The most elements `range` creates, whatever the limits, so that a typo
+/// fails with an error instead of exhausting memory.
+const MAX_RANGE_LENGTH: i128 = 10_000_000;
+
+pub type BuiltinFn = fn(Vec<Object>, &Context) -> Result<Object, RuntimeError>;
+
+/// How many arguments a builtin accepts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Arity {
+    Exact(usize),
+    Range(usize, usize),
+    AtLeast(usize),
+}
+
+impl Arity {
+    pub fn accepts(&self, count: usize) -> bool {
+        match *self {
+            Arity::Exact(n) => count == n,
+            Arity::Range(min, max) => min <= count && count <= max,
+            Arity::AtLeast(min) => min <= count,
+        }
+    }
+
+    /// Fails with a `WrongArgumentCount` error naming `function` unless it
+    /// accepts `count` arguments.
+    pub fn check(&self, function: &str, count: usize) -> Result<(), RuntimeError> {
+        if self.accepts(count) {
+            Ok(())
+        } else {
+            Err(RuntimeError::wrong_argument_count(function, *self, count))
+        }
+    }
+}
+
+impl fmt::Display for Arity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Arity::Exact(n) => write!(f, "{}", n),
+            Arity::Range(min, max) => write!(f, "{} to {}", min, max),
+            Arity::AtLeast(min) => write!(f, "at least {}", min),
+        }
+    }
+}
+
+/// A function implemented in Rust and callable from Monkey.
+#[derive(Clone, Copy)]
+pub struct Builtin {
+    pub name: &'static str,
+    pub arity: Arity,
+    pub function: BuiltinFn,
+}
+
+impl Builtin {
+    /// Checks the number of arguments against `arity` before calling the
+    /// function, so that implementations can index into `arguments` freely.
+    pub fn call(&self, arguments: Vec<Object>, ctx: &Context) -> Object {
+        let checked = self.arity.check(self.name, arguments.len());
+        match checked.and_then(|()| (self.function)(arguments, ctx)) {
+            Ok(result) => result,
+            Err(err) => Object::Error(err),
+        }
+    }
+}
+
+impl PartialEq for Builtin {
+    fn eq(&self, other: &Builtin) -> bool {
+        self.name == other.name
+    }
+}
+
+impl fmt::Debug for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Builtin({})", self.name)
+    }
+}
+
+impl fmt::Display for Builtin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "builtin function {}", self.name)
+    }
+}
+
+const BUILTINS: &[Builtin] = &[Builtin {
+                                   name: "len",
+                                   arity: Arity::Exact(1),
+                                   function: len,
+                               },
+                               Builtin {
+                                   name: "first",
+                                   arity: Arity::Exact(1),
+                                   function: first,
+                               },
+                               Builtin {
+                                   name: "last",
+                                   arity: Arity::Exact(1),
+                                   function: last,
+                               },
+                               Builtin {
+                                   name: "rest",
+                                   arity: Arity::Exact(1),
+                                   function: rest,
+                               },
+                               Builtin {
+                                   name: "push",
+                                   arity: Arity::Exact(2),
+                                   function: push,
+                               },
+                               Builtin {
+                                   name: "puts",
+                                   arity: Arity::AtLeast(0),
+                                   function: puts,
+                               },
+                               Builtin {
+                                   name: "type",
+                                   arity: Arity::Exact(1),
+                                   function: type_,
+                               },
+                               Builtin {
+                                   name: "str",
+                                   arity: Arity::Exact(1),
+                                   function: str,
+                               },
+                               Builtin {
+                                   name: "int",
+                                   arity: Arity::Exact(1),
+                                   function: int,
+                               },
+                               Builtin {
+                                   name: "keys",
+                                   arity: Arity::Exact(1),
+                                   function: keys,
+                               },
+                               Builtin {
+                                   name: "values",
+                                   arity: Arity::Exact(1),
+                                   function: values,
+                               },
+                               Builtin {
+                                   name: "range",
+                                   arity: Arity::Range(1, 3),
+                                   function: range,
+                               }];
+
+/// Finds the builtin called `name`. Identifiers bound in the environment
+/// shadow builtins.
+pub fn lookup_builtin(name: &str) -> Option<Builtin> {
+    BUILTINS.iter().find(|b| b.name == name).cloned()
+}
+
+fn array_argument<'a>(function: &'static str,
+                      position: usize,
+                      argument: &'a Object)
+                      -> Result<&'a Rc<Vec<Object>>, RuntimeError> {
+    match *argument {
+        Object::Array(ref elements) => Ok(elements),
+        ref other => {
+            Err(RuntimeError::argument_type(function, position, "ARRAY", other.type_name()))
+        }
+    }
+}
+
+fn hash_argument<'a>(function: &'static str,
+                     position: usize,
+                     argument: &'a Object)
+                     -> Result<&'a Rc<HashMap<HashKey, Object>>, RuntimeError> {
+    match *argument {
+        Object::Hash(ref pairs) => Ok(pairs),
+        ref other => {
+            Err(RuntimeError::argument_type(function, position, "HASH", other.type_name()))
+        }
+    }
+}
+
+fn integer_argument(function: &'static str,
+                    position: usize,
+                    argument: &Object)
+                    -> Result<i64, RuntimeError> {
+    match *argument {
+        Object::Integer(value) => Ok(value),
+        ref other => {
+            Err(RuntimeError::argument_type(function, position, "INTEGER", other.type_name()))
+        }
+    }
+}
+
+fn len(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    match arguments[0] {
+        Object::String(ref value) => Ok(Object::Integer(value.chars().count() as i64)),
+        Object::Array(ref elements) => Ok(Object::Integer(elements.len() as i64)),
+        Object::Hash(ref pairs) => Ok(Object::Integer(pairs.len() as i64)),
+        ref other => {
+            Err(RuntimeError::argument_type("len", 1, "STRING, ARRAY or HASH", other.type_name()))
+        }
+    }
+}
+
+fn first(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let elements = array_argument("first", 1, &arguments[0])?;
+    Ok(elements.first().cloned().unwrap_or(Object::Null))
+}
+
+fn last(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let elements = array_argument("last", 1, &arguments[0])?;
+    Ok(elements.last().cloned().unwrap_or(Object::Null))
+}
+
+/// Returns a new array holding every element but the first, or `null` for an
+/// empty array.
+fn rest(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let elements = array_argument("rest", 1, &arguments[0])?;
+    if elements.is_empty() {
+        return Ok(Object::Null);
+    }
+    Ok(Object::Array(Rc::new(elements[1..].to_vec())))
+}
+
+/// Returns a new array with the element appended; the original is unchanged.
+fn push(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let mut elements = array_argument("push", 1, &arguments[0])?.to_vec();
+    elements.push(arguments[1].clone());
+    Ok(Object::Array(Rc::new(elements)))
+}
+
+/// Prints each argument on its own line.
+fn puts(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    for argument in arguments {
+        println!("{}", argument);
+    }
+    Ok(Object::Null)
+}
+
+fn type_(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    Ok(Object::String(arguments[0].type_name().to_owned()))
+}
+
+fn str(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    Ok(Object::String(arguments[0].to_string()))
+}
+
+/// Converts a float (truncating towards zero), a boolean or a decimal string
+/// to an integer.
+fn int(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let value = match arguments[0] {
+        Object::Integer(value) => Some(value),
+        Object::Boolean(value) => Some(value as i64),
+        // `as` saturates, so only convert floats that are in range. Both bounds
+        // are exact powers of two, unlike `i64::MAX as f64`.
+        Object::Float(value) if value >= -(2f64.powi(63)) && value < 2f64.powi(63) => {
+            Some(value as i64)
+        }
+        Object::Float(_) => None,
+        Object::String(ref value) => value.trim().parse::<i64>().ok(),
+        ref other => {
+            let want = "INTEGER, FLOAT, BOOLEAN or STRING";
+            return Err(RuntimeError::argument_type("int", 1, want, other.type_name()));
+        }
+    };
+
+    match value {
+        Some(value) => Ok(Object::Integer(value)),
+        None => {
+            let message = format!("cannot convert {} to INTEGER", arguments[0].inspect());
+            Err(RuntimeError::new(message))
+        }
+    }
+}
+
+/// Returns the keys of a hash as an array, in the order they are displayed.
+fn keys(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let pairs = hash_argument("keys", 1, &arguments[0])?;
+    let mut keys = pairs.keys().cloned().collect::<Vec<HashKey>>();
+    keys.sort();
+    Ok(Object::Array(Rc::new(keys.into_iter().map(Object::from).collect())))
+}
+
+/// Returns the values of a hash as an array, ordered by their keys.
+fn values(arguments: Vec<Object>, _: &Context) -> Result<Object, RuntimeError> {
+    let mut pairs = hash_argument("values", 1, &arguments[0])?.iter().collect::<Vec<_>>();
+    pairs.sort_by(|a, b| a.0.cmp(b.0));
+    Ok(Object::Array(Rc::new(pairs.into_iter().map(|(_, value)| value.clone()).collect())))
+}
+
+/// `range(end)`, `range(start, end)` or `range(start, end, step)`: the
+/// integers from `start` (default 0) up to but excluding `end`.
+fn range(arguments: Vec<Object>, ctx: &Context) -> Result<Object, RuntimeError> {
+    let mut bounds = vec![];
+    for (i, argument) in arguments.iter().enumerate() {
+        bounds.push(integer_argument("range", i + 1, argument)?);
+    }
+
+    let (start, end, step) = match bounds[..] {
+        [end] => (0, end, 1),
+        [start, end] => (start, end, 1),
+        [start, end, step] => (start, end, step),
+        _ => unreachable!("arity is checked before the call"),
+    };
+    if step == 0 {
+        return Err(RuntimeError::new("range step must not be zero".to_owned()));
+    }
+
+    // Work out the length first, so that a range that is too large fails
+    // before anything is allocated.
+    let (start, end, step) = (i128::from(start), i128::from(end), i128::from(step));
+    let distance = if step > 0 { end - start } else { start - end };
+    let len = if distance > 0 { (distance - 1) / step.abs() + 1 } else { 0 };
+    ctx.check_collection_size(usize::try_from(len).unwrap_or(usize::MAX))?;
+    if len > MAX_RANGE_LENGTH {
+        return Err(RuntimeError::new(format!("range of {} elements is too large", len)));
+    }
+
+    let mut elements = Vec::with_capacity(len as usize);
+    elements.extend((0..len).map(|i| Object::Integer((start + i * step) as i64)));
+    Ok(Object::Array(Rc::new(elements)))
+}
@@ -0,0 +1,48 @@
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A handle for stopping an evaluation from another thread. Clones share the
+/// same flag, and once cancelled a token stays cancelled until it is reset.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        CancellationToken { cancelled: Arc::new(AtomicBool::new(false)) }
+    }
+
+    /// Asks every evaluation watching this token to stop. The evaluation
+    /// notices before its next step; a host function that is running keeps
+    /// running until it returns.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Clears the flag for every clone, so that evaluations can run again.
+    pub fn reset(&self) {
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+/// Why an evaluation was stopped before it finished.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interrupt {
+    Cancelled,
+    DeadlineExceeded,
+}
+
+impl fmt::Display for Interrupt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Interrupt::Cancelled => write!(f, "evaluation was cancelled"),
+            Interrupt::DeadlineExceeded => write!(f, "evaluation deadline exceeded"),
+        }
+    }
+}
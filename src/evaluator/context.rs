@@ -0,0 +1,118 @@
+use std::cell::Cell;
+use std::time::Instant;
+use evaluator::{Object, Limits, Limit, CancellationToken, Interrupt, RuntimeError};
+
+/// Reading the clock is much slower than evaluating a step, so the deadline
+/// is only checked this often.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// The state of a single evaluation: the limits it runs under, how much of
+/// them it has used so far, and what may interrupt it.
+#[derive(Debug, Default)]
+pub struct Context {
+    limits: Limits,
+    cancellation: Option<CancellationToken>,
+    deadline: Option<Instant>,
+    steps: Cell<usize>,
+    depth: Cell<usize>,
+    nesting: Cell<usize>,
+}
+
+impl Context {
+    pub fn new(limits: Limits) -> Self {
+        Context {
+            limits,
+            cancellation: None,
+            deadline: None,
+            steps: Cell::new(0),
+            depth: Cell::new(0),
+            nesting: Cell::new(0),
+        }
+    }
+
+    /// Stops the evaluation once `token` is cancelled.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Stops the evaluation once `deadline` has passed.
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Counts one evaluation step against `max_steps`, and checks whether
+    /// the evaluation has been cancelled or has run out of time.
+    pub fn step(&self) -> Result<(), RuntimeError> {
+        let steps = self.steps.get() + 1;
+        self.steps.set(steps);
+        check(Limit::Steps, steps, self.limits.max_steps)?;
+
+        if self.cancellation.as_ref().is_some_and(CancellationToken::is_cancelled) {
+            return Err(RuntimeError::interrupted(Interrupt::Cancelled));
+        }
+        if let Some(deadline) = self.deadline {
+            let check_clock = steps == 1 || steps.is_multiple_of(DEADLINE_CHECK_INTERVAL);
+            if check_clock && Instant::now() >= deadline {
+                return Err(RuntimeError::interrupted(Interrupt::DeadlineExceeded));
+            }
+        }
+        Ok(())
+    }
+
+    /// Enters an expression; every successful call must be paired with
+    /// `exit_expression`.
+    pub fn enter_expression(&self) -> Result<(), RuntimeError> {
+        let nesting = self.nesting.get() + 1;
+        check(Limit::NestingDepth, nesting, self.limits.max_nesting_depth)?;
+        self.nesting.set(nesting);
+        Ok(())
+    }
+
+    pub fn exit_expression(&self) {
+        self.nesting.set(self.nesting.get() - 1);
+    }
+
+    /// Enters a function call; every successful call must be paired with
+    /// `exit_call`.
+    pub fn enter_call(&self) -> Result<(), RuntimeError> {
+        let depth = self.depth.get() + 1;
+        check(Limit::CallDepth, depth, self.limits.max_call_depth)?;
+        self.depth.set(depth);
+        Ok(())
+    }
+
+    pub fn exit_call(&self) {
+        self.depth.set(self.depth.get() - 1);
+    }
+
+    /// Checks that a collection of `size` elements may be created, before
+    /// allocating it.
+    pub fn check_collection_size(&self, size: usize) -> Result<(), RuntimeError> {
+        check(Limit::CollectionSize, size, self.limits.max_collection_size)
+    }
+
+    /// Passes `object` through unless it is a string, array or hash larger
+    /// than `max_collection_size`.
+    pub fn check_size(&self, object: Object) -> Object {
+        let size = match object {
+            Object::String(ref value) => value.len(),
+            Object::Array(ref elements) => elements.len(),
+            Object::Hash(ref pairs) => pairs.len(),
+            _ => return object,
+        };
+
+        match self.check_collection_size(size) {
+            Ok(()) => object,
+            Err(err) => Object::Error(err),
+        }
+    }
+}
+
+fn check(limit: Limit, used: usize, max: Option<usize>) -> Result<(), RuntimeError> {
+    match max {
+        Some(max) if used > max => Err(RuntimeError::limit_exceeded(limit, max)),
+        _ => Ok(()),
+    }
+}
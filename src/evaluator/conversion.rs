@@ -0,0 +1,171 @@
+//! Conversions between `Object` and Rust values, for passing data across the
+//! embedding boundary.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::rc::Rc;
+use evaluator::{Object, HashKey, RuntimeError};
+
+impl From<i64> for Object {
+    fn from(value: i64) -> Self {
+        Object::Integer(value)
+    }
+}
+
+impl From<i32> for Object {
+    fn from(value: i32) -> Self {
+        Object::Integer(i64::from(value))
+    }
+}
+
+impl From<f64> for Object {
+    fn from(value: f64) -> Self {
+        Object::Float(value)
+    }
+}
+
+impl From<bool> for Object {
+    fn from(value: bool) -> Self {
+        Object::Boolean(value)
+    }
+}
+
+impl From<String> for Object {
+    fn from(value: String) -> Self {
+        Object::String(value)
+    }
+}
+
+impl<'a> From<&'a str> for Object {
+    fn from(value: &'a str) -> Self {
+        Object::String(value.to_owned())
+    }
+}
+
+impl From<()> for Object {
+    fn from(_: ()) -> Self {
+        Object::Null
+    }
+}
+
+/// `None` becomes `null`.
+impl<T: Into<Object>> From<Option<T>> for Object {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Object::Null, Into::into)
+    }
+}
+
+impl<T: Into<Object>> From<Vec<T>> for Object {
+    fn from(elements: Vec<T>) -> Self {
+        Object::Array(Rc::new(elements.into_iter().map(Into::into).collect()))
+    }
+}
+
+impl<K: Into<HashKey>, V: Into<Object>> From<HashMap<K, V>> for Object {
+    fn from(pairs: HashMap<K, V>) -> Self {
+        let pairs = pairs.into_iter().map(|(key, value)| (key.into(), value.into())).collect();
+        Object::Hash(Rc::new(pairs))
+    }
+}
+
+impl From<i64> for HashKey {
+    fn from(value: i64) -> Self {
+        HashKey::Integer(value)
+    }
+}
+
+impl From<bool> for HashKey {
+    fn from(value: bool) -> Self {
+        HashKey::Boolean(value)
+    }
+}
+
+impl From<String> for HashKey {
+    fn from(value: String) -> Self {
+        HashKey::String(value)
+    }
+}
+
+impl<'a> From<&'a str> for HashKey {
+    fn from(value: &'a str) -> Self {
+        HashKey::String(value.to_owned())
+    }
+}
+
+impl TryFrom<Object> for i64 {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Integer(value) => Ok(value),
+            other => Err(RuntimeError::unexpected_type("INTEGER", other.type_name())),
+        }
+    }
+}
+
+/// Integers are accepted too, as they are wherever Monkey expects a float.
+impl TryFrom<Object> for f64 {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Float(value) => Ok(value),
+            Object::Integer(value) => Ok(value as f64),
+            other => Err(RuntimeError::unexpected_type("FLOAT", other.type_name())),
+        }
+    }
+}
+
+impl TryFrom<Object> for bool {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Boolean(value) => Ok(value),
+            other => Err(RuntimeError::unexpected_type("BOOLEAN", other.type_name())),
+        }
+    }
+}
+
+impl TryFrom<Object> for String {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::String(value) => Ok(value),
+            other => Err(RuntimeError::unexpected_type("STRING", other.type_name())),
+        }
+    }
+}
+
+impl<T: TryFrom<Object, Error = RuntimeError>> TryFrom<Object> for Vec<T> {
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        match object {
+            Object::Array(elements) => elements.iter().cloned().map(T::try_from).collect(),
+            other => Err(RuntimeError::unexpected_type("ARRAY", other.type_name())),
+        }
+    }
+}
+
+impl<K, V> TryFrom<Object> for HashMap<K, V>
+    where K: TryFrom<Object, Error = RuntimeError> + Eq + Hash,
+          V: TryFrom<Object, Error = RuntimeError>
+{
+    type Error = RuntimeError;
+
+    fn try_from(object: Object) -> Result<Self, Self::Error> {
+        let pairs = match object {
+            Object::Hash(pairs) => pairs,
+            other => return Err(RuntimeError::unexpected_type("HASH", other.type_name())),
+        };
+
+        pairs.iter()
+            .map(|(key, value)| {
+                Ok((K::try_from(Object::from(key.clone()))?, V::try_from(value.clone())?))
+            })
+            .collect()
+    }
+}
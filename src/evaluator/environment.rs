@@ -0,0 +1,80 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use evaluator::Object;
+
+#[derive(Debug, Default)]
+pub struct Environment {
+    store: HashMap<String, Object>,
+    outer: Option<Rc<RefCell<Environment>>>,
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Environment {
+            store: HashMap::new(),
+            outer: None,
+        }
+    }
+
+    pub fn new_enclosed(outer: Rc<RefCell<Environment>>) -> Self {
+        Environment {
+            store: HashMap::new(),
+            outer: Some(outer),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Object> {
+        match self.store.get(name) {
+            Some(value) => Some(value.clone()),
+            None => {
+                match self.outer {
+                    Some(ref outer) => outer.borrow().get(name),
+                    None => None,
+                }
+            }
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: Object) -> Object {
+        self.store.insert(name.to_owned(), value.clone());
+        value
+    }
+
+    /// Rebinds an existing variable in the innermost scope that defines it.
+    /// Returns `false` if no scope does.
+    pub fn assign(&mut self, name: &str, value: Object) -> bool {
+        if let Some(slot) = self.store.get_mut(name) {
+            *slot = value;
+            return true;
+        }
+
+        match self.outer {
+            Some(ref outer) => outer.borrow_mut().assign(name, value),
+            None => false,
+        }
+    }
+}
+
+#[test]
+fn test_enclosed_environment() {
+    let outer = Rc::new(RefCell::new(Environment::new()));
+    outer.borrow_mut().set("x", Object::Integer(1));
+    outer.borrow_mut().set("y", Object::Integer(2));
+
+    let mut inner = Environment::new_enclosed(outer.clone());
+    inner.set("y", Object::Integer(3));
+
+    assert_eq!(Some(Object::Integer(1)), inner.get("x"));
+    assert_eq!(Some(Object::Integer(3)), inner.get("y"));
+    assert_eq!(Some(Object::Integer(2)), outer.borrow().get("y"));
+    assert_eq!(None, inner.get("z"));
+
+    assert!(inner.assign("x", Object::Integer(4)));
+    assert!(inner.assign("y", Object::Integer(5)));
+    assert!(!inner.assign("z", Object::Integer(6)));
+    assert_eq!(Some(Object::Integer(4)), outer.borrow().get("x"));
+    assert_eq!(Some(Object::Integer(2)), outer.borrow().get("y"));
+    assert_eq!(Some(Object::Integer(5)), inner.get("y"));
+    assert_eq!(None, inner.get("z"));
+}
@@ -0,0 +1,1093 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+use ast::{Program, Statement, Expression, BlockStatement, Identifier, InfixExpression,
+          AssignExpression, IfExpression, CallExpression, IndexExpression, HashLiteral};
+use evaluator::{Object, Function, Environment, Context, RuntimeError, lookup_builtin};
+
+/// Evaluates `program` without any limits.
+pub fn eval(program: &Program, env: &Rc<RefCell<Environment>>) -> Object {
+    eval_with_context(program, env, &Context::default())
+}
+
+/// Evaluates `program` under the limits of `ctx`.
+pub fn eval_with_context(program: &Program,
+                         env: &Rc<RefCell<Environment>>,
+                         ctx: &Context)
+                         -> Object {
+    let mut result = Object::Null;
+
+    for statement in &program.statements {
+        result = eval_statement(statement, env, ctx);
+
+        match result {
+            Object::ReturnValue(value) => return *value,
+            Object::Error(_) => return result,
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn eval_statement(statement: &Statement,
+                  env: &Rc<RefCell<Environment>>,
+                  ctx: &Context)
+                  -> Object {
+    match *statement {
+        Statement::Expression(ref s) => eval_expression(&s.expression, env, ctx),
+        Statement::Let(ref s) => {
+            let value = eval_expression(&s.value, env, ctx);
+            if value.is_return_or_error() {
+                return value;
+            }
+            env.borrow_mut().set(&s.name.value, value);
+            Object::Null
+        }
+        Statement::Return(ref s) => {
+            let value = eval_expression(&s.return_value, env, ctx);
+            if value.is_return_or_error() {
+                return value;
+            }
+            Object::ReturnValue(Box::new(value))
+        }
+    }
+}
+
+fn eval_block_statement(block: &BlockStatement,
+                        env: &Rc<RefCell<Environment>>,
+                        ctx: &Context)
+                        -> Object {
+    let mut result = Object::Null;
+
+    for statement in &block.statements {
+        result = eval_statement(statement, env, ctx);
+
+        match result {
+            Object::ReturnValue(_) |
+            Object::Error(_) => return result,
+            _ => {}
+        }
+    }
+
+    result
+}
+
+fn eval_expression(expression: &Expression,
+                   env: &Rc<RefCell<Environment>>,
+                   ctx: &Context)
+                   -> Object {
+    if let Err(err) = ctx.step().and_then(|()| ctx.enter_expression()) {
+        return Object::Error(err.or_span(expression.span()));
+    }
+    let result = eval_expression_kind(expression, env, ctx);
+    ctx.exit_expression();
+
+    match result {
+        Object::Error(err) => Object::Error(err.or_span(expression.span())),
+        result => result,
+    }
+}
+
+fn eval_expression_kind(expression: &Expression,
+                        env: &Rc<RefCell<Environment>>,
+                        ctx: &Context)
+                        -> Object {
+    match *expression {
+        Expression::IntegerLiteral(ref e) => Object::Integer(e.value),
+        Expression::FloatLiteral(ref e) => Object::Float(e.value),
+        Expression::StringLiteral(ref e) => Object::String(e.value.clone()),
+        Expression::Boolean(ref e) => Object::Boolean(e.value),
+        Expression::Identifier(ref e) => eval_identifier(e, env),
+        Expression::Prefix(ref e) => {
+            let right = eval_expression(&e.right, env, ctx);
+            if right.is_return_or_error() {
+                return right;
+            }
+            eval_prefix_expression(&e.operator, right)
+        }
+        Expression::Infix(ref e) if e.operator == "&&" || e.operator == "||" => {
+            eval_logical_expression(e, env, ctx)
+        }
+        Expression::Infix(ref e) => {
+            let left = eval_expression(&e.left, env, ctx);
+            if left.is_return_or_error() {
+                return left;
+            }
+            let right = eval_expression(&e.right, env, ctx);
+            if right.is_return_or_error() {
+                return right;
+            }
+            ctx.check_size(eval_infix_expression(&e.operator, left, right))
+        }
+        Expression::Assign(ref e) => eval_assign_expression(e, env, ctx),
+        Expression::If(ref e) => eval_if_expression(e, env, ctx),
+        Expression::FunctionLiteral(ref e) => {
+            Object::Function(Function::new(e.parameters.clone(), e.body.clone(), env.clone()))
+        }
+        Expression::Call(ref e) => eval_call_expression(e, env, ctx),
+        Expression::Array(ref e) => {
+            match eval_expressions(&e.elements, env, ctx) {
+                Ok(elements) => ctx.check_size(Object::Array(Rc::new(elements))),
+                Err(err) => err,
+            }
+        }
+        Expression::Index(ref e) => eval_index_expression(e, env, ctx),
+        Expression::Hash(ref e) => ctx.check_size(eval_hash_literal(e, env, ctx)),
+    }
+}
+
+fn eval_call_expression(call: &CallExpression,
+                        env: &Rc<RefCell<Environment>>,
+                        ctx: &Context)
+                        -> Object {
+    let function = eval_expression(&call.function, env, ctx);
+    if function.is_return_or_error() {
+        return function;
+    }
+
+    match eval_expressions(&call.arguments, env, ctx) {
+        Ok(arguments) => apply_function(function, arguments, ctx),
+        Err(err) => err,
+    }
+}
+
+/// Evaluates `expressions` from left to right, stopping at the first error or
+/// `return`.
+fn eval_expressions(expressions: &[Expression],
+                    env: &Rc<RefCell<Environment>>,
+                    ctx: &Context)
+                    -> Result<Vec<Object>, Object> {
+    let mut values = vec![];
+    for expression in expressions {
+        let value = eval_expression(expression, env, ctx);
+        if value.is_return_or_error() {
+            return Err(value);
+        }
+        values.push(value);
+    }
+    Ok(values)
+}
+
+fn eval_index_expression(expression: &IndexExpression,
+                         env: &Rc<RefCell<Environment>>,
+                         ctx: &Context)
+                         -> Object {
+    let left = eval_expression(&expression.left, env, ctx);
+    if left.is_return_or_error() {
+        return left;
+    }
+    let index = eval_expression(&expression.index, env, ctx);
+    if index.is_return_or_error() {
+        return index;
+    }
+
+    match (left, index) {
+        // Indexing outside the array, including with a negative index, yields null.
+        (Object::Array(elements), Object::Integer(index)) => {
+            usize::try_from(index)
+                .ok()
+                .and_then(|index| elements.get(index).cloned())
+                .unwrap_or(Object::Null)
+        }
+        (Object::Array(_), index) => {
+            Object::error(format!("array index must be INTEGER, got {}", index.type_name()))
+        }
+        (Object::Hash(pairs), index) => {
+            match index.hash_key() {
+                Some(key) => pairs.get(&key).cloned().unwrap_or(Object::Null),
+                None => Object::Error(unusable_as_hash_key(&index)),
+            }
+        }
+        (left, _) => {
+            Object::error(format!("index operator not supported: {}", left.type_name()))
+        }
+    }
+}
+
+
+fn eval_hash_literal(hash: &HashLiteral,
+                     env: &Rc<RefCell<Environment>>,
+                     ctx: &Context)
+                     -> Object {
+    let mut pairs = HashMap::new();
+
+    for (key_expression, value_expression) in &hash.pairs {
+        let key = eval_expression(key_expression, env, ctx);
+        if key.is_return_or_error() {
+            return key;
+        }
+        let hash_key = match key.hash_key() {
+            Some(hash_key) => hash_key,
+            None => {
+                let err = unusable_as_hash_key(&key).or_span(key_expression.span());
+                return Object::Error(err);
+            }
+        };
+
+        let value = eval_expression(value_expression, env, ctx);
+        if value.is_return_or_error() {
+            return value;
+        }
+
+        pairs.insert(hash_key, value);
+    }
+
+    Object::Hash(Rc::new(pairs))
+}
+
+fn unusable_as_hash_key(key: &Object) -> RuntimeError {
+    RuntimeError::new(format!("unusable as hash key: {}", key.type_name()))
+}
+
+fn eval_identifier(identifier: &Identifier, env: &Rc<RefCell<Environment>>) -> Object {
+    let value = env.borrow().get(&identifier.value);
+    match value {
+        Some(value) => value,
+        None => {
+            match lookup_builtin(&identifier.value) {
+                Some(builtin) => Object::Builtin(builtin),
+                None => Object::error(format!("identifier not found: {}", identifier.value)),
+            }
+        }
+    }
+}
+
+/// `&&` and `||` only evaluate their right operand when the left one doesn't
+/// already decide the result, and always produce a boolean.
+fn eval_logical_expression(expression: &InfixExpression,
+                           env: &Rc<RefCell<Environment>>,
+                           ctx: &Context)
+                           -> Object {
+    let left = eval_expression(&expression.left, env, ctx);
+    if left.is_return_or_error() {
+        return left;
+    }
+
+    if left.is_truthy() == (expression.operator == "||") {
+        return Object::Boolean(left.is_truthy());
+    }
+
+    let right = eval_expression(&expression.right, env, ctx);
+    if right.is_return_or_error() {
+        return right;
+    }
+    Object::Boolean(right.is_truthy())
+}
+
+/// Evaluates `x op= value` as `x = x op value`, updating `x` in the scope
+/// that defines it.
+fn eval_assign_expression(expression: &AssignExpression,
+                          env: &Rc<RefCell<Environment>>,
+                          ctx: &Context)
+                          -> Object {
+    let current = eval_identifier(&expression.name, env);
+    if current.is_return_or_error() {
+        return current;
+    }
+    let value = eval_expression(&expression.value, env, ctx);
+    if value.is_return_or_error() {
+        return value;
+    }
+
+    let operator = expression.operator.trim_end_matches('=');
+    let result = ctx.check_size(eval_infix_expression(operator, current, value));
+    if !result.is_error() {
+        env.borrow_mut().assign(&expression.name.value, result.clone());
+    }
+    result
+}
+
+fn eval_prefix_expression(operator: &str, right: Object) -> Object {
+    match operator {
+        "!" => Object::Boolean(!right.is_truthy()),
+        "-" => {
+            match right {
+                Object::Integer(value) => {
+                    match value.checked_neg() {
+                        Some(value) => Object::Integer(value),
+                        None => Object::error(format!("integer overflow: -({})", value)),
+                    }
+                }
+                Object::Float(value) => Object::Float(-value),
+                _ => Object::error(format!("unknown operator: -{}", right.type_name())),
+            }
+        }
+        _ => Object::error(format!("unknown operator: {}{}", operator, right.type_name())),
+    }
+}
+
+fn eval_infix_expression(operator: &str, left: Object, right: Object) -> Object {
+    match (left, right) {
+        (Object::Integer(left), Object::Integer(right)) => {
+            eval_integer_infix_expression(operator, left, right)
+        }
+        (Object::Float(left), Object::Float(right)) => {
+            eval_float_infix_expression(operator, left, right)
+        }
+        // Mixing integers and floats promotes the integer to a float.
+        (Object::Integer(left), Object::Float(right)) => {
+            eval_float_infix_expression(operator, left as f64, right)
+        }
+        (Object::Float(left), Object::Integer(right)) => {
+            eval_float_infix_expression(operator, left, right as f64)
+        }
+        (Object::Boolean(left), Object::Boolean(right)) => {
+            match operator {
+                "==" => Object::Boolean(left == right),
+                "!=" => Object::Boolean(left != right),
+                _ => Object::error(format!("unknown operator: BOOLEAN {} BOOLEAN", operator)),
+            }
+        }
+        (Object::String(left), Object::String(right)) => {
+            eval_string_infix_expression(operator, left, &right)
+        }
+        (left, right) => {
+            if left.type_name() != right.type_name() {
+                Object::error(format!("type mismatch: {} {} {}",
+                                      left.type_name(),
+                                      operator,
+                                      right.type_name()))
+            } else {
+                Object::error(format!("unknown operator: {} {} {}",
+                                      left.type_name(),
+                                      operator,
+                                      right.type_name()))
+            }
+        }
+    }
+}
+
+/// Integer arithmetic is checked: a result that doesn't fit in an `i64` is an
+/// error rather than a wrapped value.
+fn eval_integer_infix_expression(operator: &str, left: i64, right: i64) -> Object {
+    let result = match operator {
+        "+" => left.checked_add(right),
+        "-" => left.checked_sub(right),
+        "*" => left.checked_mul(right),
+        "/" | "%" if right == 0 => return Object::error("division by zero".to_owned()),
+        "/" => left.checked_div(right),
+        "%" => left.checked_rem(right),
+        // A negative exponent can't produce an integer.
+        "**" if right < 0 => {
+            return eval_float_infix_expression(operator, left as f64, right as f64)
+        }
+        "**" => u32::try_from(right).ok().and_then(|right| left.checked_pow(right)),
+        "<" => return Object::Boolean(left < right),
+        ">" => return Object::Boolean(left > right),
+        "<=" => return Object::Boolean(left <= right),
+        ">=" => return Object::Boolean(left >= right),
+        "==" => return Object::Boolean(left == right),
+        "!=" => return Object::Boolean(left != right),
+        _ => return Object::error(format!("unknown operator: INTEGER {} INTEGER", operator)),
+    };
+
+    match result {
+        Some(value) => Object::Integer(value),
+        None => Object::error(format!("integer overflow: {} {} {}", left, operator, right)),
+    }
+}
+
+fn eval_float_infix_expression(operator: &str, left: f64, right: f64) -> Object {
+    match operator {
+        "+" => Object::Float(left + right),
+        "-" => Object::Float(left - right),
+        "*" => Object::Float(left * right),
+        "/" | "%" if right == 0.0 => Object::error("division by zero".to_owned()),
+        "/" => Object::Float(left / right),
+        "%" => Object::Float(left % right),
+        "**" => Object::Float(left.powf(right)),
+        "<" => Object::Boolean(left < right),
+        ">" => Object::Boolean(left > right),
+        "<=" => Object::Boolean(left <= right),
+        ">=" => Object::Boolean(left >= right),
+        "==" => Object::Boolean(left == right),
+        "!=" => Object::Boolean(left != right),
+        _ => Object::error(format!("unknown operator: FLOAT {} FLOAT", operator)),
+    }
+}
+
+fn eval_string_infix_expression(operator: &str, left: String, right: &str) -> Object {
+    match operator {
+        "+" => Object::String(left + right),
+        "==" => Object::Boolean(left == right),
+        "!=" => Object::Boolean(left != right),
+        _ => Object::error(format!("unknown operator: STRING {} STRING", operator)),
+    }
+}
+
+fn eval_if_expression(expression: &IfExpression,
+                      env: &Rc<RefCell<Environment>>,
+                      ctx: &Context)
+                      -> Object {
+    let condition = eval_expression(&expression.condition, env, ctx);
+    if condition.is_return_or_error() {
+        return condition;
+    }
+
+    if condition.is_truthy() {
+        eval_block_statement(&expression.consequence, env, ctx)
+    } else if let Some(ref alternative) = expression.alternative {
+        eval_block_statement(alternative, env, ctx)
+    } else {
+        Object::Null
+    }
+}
+
+/// Calls `function`, which may be a Monkey function, a builtin or a host
+/// function, with already evaluated `arguments`.
+pub fn apply_function(function: Object, arguments: Vec<Object>, ctx: &Context) -> Object {
+    let function = match function {
+        Object::Function(function) => function,
+        Object::Builtin(builtin) => return ctx.check_size(builtin.call(arguments, ctx)),
+        Object::HostFunction(function) => return ctx.check_size(function.call(&arguments)),
+        other => return Object::error(format!("not a function: {}", other.type_name())),
+    };
+
+    if function.parameters.len() != arguments.len() {
+        return Object::error(format!("wrong number of arguments: want={}, got={}",
+                                     function.parameters.len(),
+                                     arguments.len()));
+    }
+
+    let mut function_env = Environment::new_enclosed(function.env.clone());
+    for (parameter, argument) in function.parameters.iter().zip(arguments) {
+        function_env.set(&parameter.value, argument);
+    }
+
+    if let Err(err) = ctx.enter_call() {
+        return Object::Error(err);
+    }
+    let result = eval_block_statement(&function.body, &Rc::new(RefCell::new(function_env)), ctx);
+    ctx.exit_call();
+
+    match result {
+        Object::ReturnValue(value) => *value,
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lexer::Lexer;
+    use parser::Parser;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use token::Span;
+    use evaluator::{eval, eval_with_context, Object, Environment, Context, Limits, Limit, HashKey,
+                    Arity, RuntimeErrorKind};
+
+    fn test_eval(input: &str) -> Object {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parser_program().unwrap();
+        let env = Rc::new(RefCell::new(Environment::new()));
+
+        eval(&program, &env)
+    }
+
+    fn test_eval_with_limits(input: &str, limits: Limits) -> Object {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+        let program = parser.parser_program().unwrap();
+        let env = Rc::new(RefCell::new(Environment::new()));
+
+        eval_with_context(&program, &env, &Context::new(limits))
+    }
+
+    #[test]
+    fn test_eval_integer_expression() {
+        let tests = vec![("5", 5),
+                         ("10", 10),
+                         ("-5", -5),
+                         ("-10", -10),
+                         ("5 + 5 + 5 + 5 - 10", 10),
+                         ("2 * 2 * 2 * 2 * 2", 32),
+                         ("-50 + 100 + -50", 0),
+                         ("5 * 2 + 10", 20),
+                         ("5 + 2 * 10", 25),
+                         ("20 + 2 * -10", 0),
+                         ("50 / 2 * 2 + 10", 60),
+                         ("2 * (5 + 10)", 30),
+                         ("3 * 3 * 3 + 10", 37),
+                         ("3 * (3 * 3) + 10", 37),
+                         ("(5 + 10 * 2 + 15 / 3) * 2 + -10", 50),
+                         ("7 % 3", 1),
+                         ("-7 % 3", -1),
+                         ("2 ** 10", 1024),
+                         ("2 ** 3 ** 2", 512),
+                         ("-2 ** 2", -4),
+                         ("(-2) ** 3", -8),
+                         ("5 ** 0", 1),
+                         ("1 + 2 * 3 ** 2 % 5", 4)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Integer(expected), test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_eval_float_expression() {
+        let tests = vec![("2.75", 2.75),
+                         ("-2.5", -2.5),
+                         ("1e3", 1000.0),
+                         ("0.5 + 0.25", 0.75),
+                         ("1.5 * 2.0 - 1.0", 2.0),
+                         ("1 + 0.5", 1.5),
+                         ("0.5 + 1", 1.5),
+                         ("7 / 2.0", 3.5),
+                         ("2 * (0.5 + 1)", 3.0)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Float(expected), test_eval(input));
+        }
+
+        let tests = vec![("0xFF + 0b1 + 0o7 + 1_000", Object::Integer(1263)),
+                         ("7.5 % 2", Object::Float(1.5)),
+                         ("2.0 ** 0.5 * 2.0 ** 0.5 > 1.99", Object::Boolean(true)),
+                         ("2 ** -1", Object::Float(0.5)),
+                         ("1.5 <= 1", Object::Boolean(false)),
+                         ("1 >= 0.5", Object::Boolean(true)),
+                         ("7 / 2", Object::Integer(3)),
+                         ("1 < 1.5", Object::Boolean(true)),
+                         ("2.0 == 2", Object::Boolean(true)),
+                         ("0.1 + 0.2 != 0.3", Object::Boolean(true))];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+
+        assert_eq!("3.0", test_eval("1.5 * 2").to_string());
+    }
+
+    #[test]
+    fn test_eval_boolean_expression() {
+        let tests = vec![("true", true),
+                         ("false", false),
+                         ("1 < 2", true),
+                         ("1 > 2", false),
+                         ("1 == 1", true),
+                         ("1 != 1", false),
+                         ("true == true", true),
+                         ("true != false", true),
+                         ("(1 < 2) == true", true),
+                         ("(1 > 2) == true", false),
+                         ("!true", false),
+                         ("!5", false),
+                         ("!!true", true),
+                         ("!!5", true),
+                         ("1 <= 2", true),
+                         ("2 <= 2", true),
+                         ("3 <= 2", false),
+                         ("1 >= 2", false),
+                         ("2 >= 2", true),
+                         ("true && true", true),
+                         ("true && false", false),
+                         ("false || true", true),
+                         ("false || false", false),
+                         ("1 && 0", true),
+                         ("if (false) { 1 } || 0", true),
+                         ("1 < 2 && 2 < 3 || false", true)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Boolean(expected), test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_string_expression() {
+        let tests = vec![(r#""Hello World!""#, Object::String("Hello World!".to_owned())),
+                         (r#""Hello" + " " + "World!""#,
+                          Object::String("Hello World!".to_owned())),
+                         (r#"let greet = fn(name) { "hi, " + name }; greet("monkey")"#,
+                          Object::String("hi, monkey".to_owned())),
+                         (r#""a" == "a""#, Object::Boolean(true)),
+                         (r#""a" == "b""#, Object::Boolean(false)),
+                         (r#""a" != "b""#, Object::Boolean(true)),
+                         (r#""a" + "b" == "ab""#, Object::Boolean(true))];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_if_else_expression() {
+        let tests = vec![("if (true) { 10 }", Object::Integer(10)),
+                         ("if (false) { 10 }", Object::Null),
+                         ("if (1) { 10 }", Object::Integer(10)),
+                         ("if (1 < 2) { 10 }", Object::Integer(10)),
+                         ("if (1 > 2) { 10 } else { 20 }", Object::Integer(20)),
+                         ("if (1 < 2) { 10 } else { 20 }", Object::Integer(10))];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_return_statement() {
+        let tests = vec![("return 10;", 10),
+                         ("return 10; 9;", 10),
+                         ("return 2 * 5; 9;", 10),
+                         ("9; return 2 * 5; 9;", 10),
+                         ("if (10 > 1) { if (10 > 1) { return 10; } return 1; }", 10)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Integer(expected), test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_return_in_expression() {
+        let ret = "if (true) { return 5; }";
+        let tests = vec![(format!("let f = fn() {{ let x = {}; 10 }}; f()", ret), 5),
+                         (format!("let f = fn() {{ [{}, 10] }}; f()", ret), 5),
+                         (format!("let f = fn() {{ {{1: {}}} }}; f()", ret), 5),
+                         (format!("let f = fn() {{ {{{}: 1}} }}; f()", ret), 5),
+                         (format!("let f = fn() {{ puts({}); 10 }}; f()", ret), 5),
+                         (format!("let f = fn() {{ 1 + {} }}; f()", ret), 5),
+                         (format!("let f = fn() {{ ({}) + 1 }}; f()", ret), 5),
+                         (format!("let f = fn() {{ -{} }}; f()", ret), 5),
+                         (format!("let f = fn() {{ [1][{}] }}; f()", ret), 5),
+                         (format!("let f = fn() {{ ({})[0] }}; f()", ret), 5),
+                         (format!("let f = fn() {{ if ({}) {{ 10 }} }}; f()", ret), 5),
+                         (format!("let f = fn() {{ let x = 1; x += {}; 10 }}; f()", ret), 5),
+                         (format!("let f = fn() {{ false || {}; 10 }}; f()", ret), 5),
+                         (format!("let f = fn() {{ return {}; }}; f()", ret), 5),
+                         (format!("let f = fn() {{ let x = {}; 10 }}; f() + 1", ret), 6)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Integer(expected), test_eval(&input), "{}", input);
+        }
+
+        assert_eq!(Object::Integer(5), test_eval(&format!("let x = {}; 10", ret)));
+    }
+
+    #[test]
+    fn test_error_handling() {
+        let tests = vec![("5 + true;", "type mismatch: INTEGER + BOOLEAN"),
+                         ("5 + true; 5;", "type mismatch: INTEGER + BOOLEAN"),
+                         ("-true", "unknown operator: -BOOLEAN"),
+                         ("true + false;", "unknown operator: BOOLEAN + BOOLEAN"),
+                         ("5; true + false; 5", "unknown operator: BOOLEAN + BOOLEAN"),
+                         ("if (10 > 1) { true + false; }", "unknown operator: BOOLEAN + BOOLEAN"),
+                         ("if (10 > 1) { if (10 > 1) { return true + false; } return 1; }",
+                          "unknown operator: BOOLEAN + BOOLEAN"),
+                         ("foobar", "identifier not found: foobar"),
+                         (r#""Hello" - "World""#, "unknown operator: STRING - STRING"),
+                         (r#""a" + 1"#, "type mismatch: STRING + INTEGER"),
+                         ("10 / 0", "division by zero"),
+                         ("len(1)",
+                          "argument 1 to `len` must be STRING, ARRAY or HASH, got INTEGER"),
+                         (r#"len("one", "two")"#,
+                          "wrong number of arguments to `len`: want=1, got=2"),
+                         ("push([])", "wrong number of arguments to `push`: want=2, got=1"),
+                         ("first(1)", "argument 1 to `first` must be ARRAY, got INTEGER"),
+                         ("rest(\"abc\")", "argument 1 to `rest` must be ARRAY, got STRING"),
+                         ("keys([])", "argument 1 to `keys` must be HASH, got ARRAY"),
+                         ("range(0, \"3\")", "argument 2 to `range` must be INTEGER, got STRING"),
+                         ("range()", "wrong number of arguments to `range`: want=1 to 3, got=0"),
+                         ("range(0, 3, 0)", "range step must not be zero"),
+                         ("range(9223372036854775807)",
+                          "range of 9223372036854775807 elements is too large"),
+                         ("range(-9223372036854775807 - 1, 9223372036854775807)",
+                          "range of 18446744073709551615 elements is too large"),
+                         ("range(0, 1000000000)",
+                          "range of 1000000000 elements is too large"),
+                         ("int(9223372036854775808.0)",
+                          "cannot convert 9.223372036854776e18 to INTEGER"),
+                         ("int(\"12a\")", "cannot convert \"12a\" to INTEGER"),
+                         ("int(1e300)", "cannot convert 1e300 to INTEGER"),
+                         ("int([])",
+                          "argument 1 to `int` must be INTEGER, FLOAT, BOOLEAN or STRING, \
+                           got ARRAY"),
+                         ("[1, 2][true]", "array index must be INTEGER, got BOOLEAN"),
+                         ("1[0]", "index operator not supported: INTEGER"),
+                         (r#"{"name": "Monkey"}[fn(x) { x }];"#, "unusable as hash key: FUNCTION"),
+                         ("{[1]: 2}", "unusable as hash key: ARRAY"),
+                         ("{1: 2}[{}]", "unusable as hash key: HASH"),
+                         ("{1.5: 2}", "unusable as hash key: FLOAT"),
+                         ("[1, foobar]", "identifier not found: foobar"),
+                         ("10 % 0", "division by zero"),
+                         ("2 ** 64", "integer overflow: 2 ** 64"),
+                         ("2 ** 4294967296", "integer overflow: 2 ** 4294967296"),
+                         ("true <= false", "unknown operator: BOOLEAN <= BOOLEAN"),
+                         ("true && foobar", "identifier not found: foobar"),
+                         ("x += 1", "identifier not found: x"),
+                         ("let x = 1; x += true", "type mismatch: INTEGER + BOOLEAN"),
+                         ("9223372036854775807 + 1",
+                          "integer overflow: 9223372036854775807 + 1"),
+                         ("-9223372036854775807 - 2",
+                          "integer overflow: -9223372036854775807 - 2"),
+                         ("4611686018427387904 * 2",
+                          "integer overflow: 4611686018427387904 * 2"),
+                         ("let min = -9223372036854775807 - 1; min / -1",
+                          "integer overflow: -9223372036854775808 / -1"),
+                         ("let min = -9223372036854775807 - 1; -min",
+                          "integer overflow: -(-9223372036854775808)"),
+                         ("1.5 / 0", "division by zero"),
+                         ("1.5 + true", "type mismatch: FLOAT + BOOLEAN"),
+                         ("-\"a\"", "unknown operator: -STRING"),
+                         ("5(1)", "not a function: INTEGER"),
+                         ("let f = fn(x) { x }; f(1, 2)",
+                          "wrong number of arguments: want=1, got=2")];
+
+        for (input, expected) in tests {
+            match test_eval(input) {
+                Object::Error(err) => assert_eq!(expected, err.to_string()),
+                other => panic!("no error object returned. got={}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_error_span() {
+        let tests = vec![("5 + true;", Span::new(0, 8, 1, 1)),
+                         ("let x = 1;\nx + foobar", Span::new(15, 21, 2, 5)),
+                         ("let f = fn() { -true };\nf()", Span::new(15, 20, 1, 16)),
+                         ("let x = 1; x(2)", Span::new(11, 15, 1, 12)),
+                         ("{1: 1, len: 2}", Span::new(7, 10, 1, 8))];
+
+        for (input, expected) in tests {
+            match test_eval(input) {
+                Object::Error(err) => assert_eq!(Some(expected), err.span),
+                other => panic!("no error object returned. got={}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_let_statement() {
+        let tests = vec![("let a = 5; a;", 5),
+                         ("let a = 5 * 5; a;", 25),
+                         ("let a = 5; let b = a; b;", 5),
+                         ("let a = 5; let b = a; let c = a + b + 5; c;", 15),
+                         ("let x1 = 1; let x2 = 2; x1 + x2;", 3),
+                         ("let größe = 4; let 変数 = größe * 2; 変数;", 8)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Integer(expected), test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_short_circuit() {
+        let tests = vec![("false && foobar", false),
+                         ("true || foobar", true),
+                         ("let x = 0; let f = fn() { x += 1; true }; false && f(); x == 0", true),
+                         ("let x = 0; let f = fn() { x += 1; true }; true && f(); x == 1", true)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Boolean(expected), test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_assign_expression() {
+        let tests = vec![("let x = 1; x += 2; x", Object::Integer(3)),
+                         ("let x = 10; x -= 2 * 3", Object::Integer(4)),
+                         ("let x = 3; x *= x; x", Object::Integer(9)),
+                         ("let x = 7; x /= 2; x", Object::Integer(3)),
+                         ("let x = 1.0; x /= 4; x", Object::Float(0.25)),
+                         ("let s = \"a\"; s += \"b\"; s", Object::String("ab".to_owned())),
+                         ("let a = 1; let b = 2; a += b += 3; a + b", Object::Integer(11)),
+                         ("let counter = fn() { let n = 0; fn() { n += 1 } }; \
+                           let next = counter(); next(); next(); next()",
+                          Object::Integer(3)),
+                         ("let x = 1; let f = fn(x) { x += 1; x }; f(10) + x",
+                          Object::Integer(12))];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_function_application() {
+        let tests = vec![("let identity = fn(x) { x; }; identity(5);", 5),
+                         ("let identity = fn(x) { return x; }; identity(5);", 5),
+                         ("let double = fn(x) { x * 2; }; double(5);", 10),
+                         ("let add = fn(x, y) { x + y; }; add(5, 5);", 10),
+                         ("let add = fn(x, y) { x + y; }; add(5 + 5, add(5, 5));", 20),
+                         ("fn(x) { x; }(5)", 5)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Integer(expected), test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_closures() {
+        let tests = vec![("let newAdder = fn(x) { fn(y) { x + y }; }; \
+                           let addTwo = newAdder(2); \
+                           addTwo(2);",
+                          4),
+                         ("let adder = fn(x) { fn(y) { x + y } }; \
+                           let addOne = adder(1); \
+                           let addTen = adder(10); \
+                           addOne(1) + addTen(1);",
+                          13),
+                         ("let x = 10; let f = fn() { x }; let x = 20; f();", 20),
+                         ("let x = 1; let f = fn(x) { let y = x * 2; y }; f(5) + x;", 11),
+                         ("let fib = fn(n) { if (n < 2) { return n; } fib(n - 1) + fib(n - 2) }; \
+                           fib(10);",
+                          55)];
+
+        for (input, expected) in tests {
+            assert_eq!(Object::Integer(expected), test_eval(input));
+        }
+
+        match test_eval("let f = fn(x) { let y = x; y }; f(1); y") {
+            Object::Error(err) => assert_eq!("identifier not found: y", err.to_string()),
+            other => panic!("no error object returned. got={}", other),
+        }
+    }
+
+    #[test]
+    fn test_array_literal() {
+        let elements = vec![Object::Integer(1), Object::Integer(4), Object::Integer(6)];
+        assert_eq!(Object::Array(Rc::new(elements)), test_eval("[1, 2 * 2, 3 + 3]"));
+
+        assert_eq!(r#"[1, "two", [true], []]"#,
+                   test_eval(r#"[1, "two", [true], []]"#).to_string());
+    }
+
+    #[test]
+    fn test_array_index_expression() {
+        let tests = vec![("[1, 2, 3][0]", Object::Integer(1)),
+                         ("[1, 2, 3][1]", Object::Integer(2)),
+                         ("[1, 2, 3][2]", Object::Integer(3)),
+                         ("let i = 0; [1][i];", Object::Integer(1)),
+                         ("[1, 2, 3][1 + 1];", Object::Integer(3)),
+                         ("let myArray = [1, 2, 3]; myArray[2];", Object::Integer(3)),
+                         ("let myArray = [1, 2, 3]; myArray[0] + myArray[1] + myArray[2];",
+                          Object::Integer(6)),
+                         ("let myArray = [1, 2, 3]; let i = myArray[0]; myArray[i]",
+                          Object::Integer(2)),
+                         ("[[1, 2], [3]][1][0]", Object::Integer(3)),
+                         ("[1, 2, 3][3]", Object::Null),
+                         ("[1, 2, 3][-1]", Object::Null),
+                         ("[][0]", Object::Null)];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_hash_literal() {
+        let input = r#"
+        let two = "two";
+        {
+            "one": 10 - 9,
+            two: 1 + 1,
+            "thr" + "ee": 6 / 2,
+            4: 4,
+            true: 5,
+            false: 6
+        }
+        "#;
+
+        let pairs = match test_eval(input) {
+            Object::Hash(pairs) => pairs,
+            other => panic!("object is not Hash. got={}", other),
+        };
+
+        let expected = vec![(HashKey::String("one".to_owned()), 1),
+                            (HashKey::String("two".to_owned()), 2),
+                            (HashKey::String("three".to_owned()), 3),
+                            (HashKey::Integer(4), 4),
+                            (HashKey::Boolean(true), 5),
+                            (HashKey::Boolean(false), 6)];
+        assert_eq!(expected.len(), pairs.len());
+        for (key, value) in expected {
+            assert_eq!(Some(&Object::Integer(value)), pairs.get(&key));
+        }
+
+        assert_eq!(r#"{1: "a", true: [2], "b": {}}"#,
+                   test_eval(r#"{"b": {}, true: [2], 1: "a"}"#).to_string());
+        assert_eq!("{1: 2}", test_eval("{1: 1, 1: 2}").to_string());
+    }
+
+    #[test]
+    fn test_hash_index_expression() {
+        let tests = vec![(r#"{"foo": 5}["foo"]"#, Object::Integer(5)),
+                         (r#"{"foo": 5}["bar"]"#, Object::Null),
+                         (r#"let key = "foo"; {"foo": 5}[key]"#, Object::Integer(5)),
+                         (r#"{}["foo"]"#, Object::Null),
+                         ("{5: 5}[5]", Object::Integer(5)),
+                         ("{true: 5}[true]", Object::Integer(5)),
+                         ("{false: 5}[false]", Object::Integer(5)),
+                         ("{1: {2: 3}}[1][2]", Object::Integer(3))];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+    }
+
+    #[test]
+    fn test_builtin_functions() {
+        let tests = vec![(r#"len("")"#, Object::Integer(0)),
+                         (r#"len("four")"#, Object::Integer(4)),
+                         (r#"len("hello world")"#, Object::Integer(11)),
+                         (r#"len("héllo")"#, Object::Integer(5)),
+                         ("len([1, 2, 3])", Object::Integer(3)),
+                         ("len([])", Object::Integer(0)),
+                         ("first([1, 2, 3])", Object::Integer(1)),
+                         ("first([])", Object::Null),
+                         ("last([1, 2, 3])", Object::Integer(3)),
+                         ("last([])", Object::Null),
+                         ("rest([])", Object::Null),
+                         ("len(rest(rest([1, 2, 3])))", Object::Integer(1)),
+                         ("let a = [1]; let b = push(a, 2); len(a) * 10 + len(b)",
+                          Object::Integer(12)),
+                         ("let len = fn(x) { 42 }; len([])", Object::Integer(42)),
+                         (r#"len({"a": 1, "b": 2})"#, Object::Integer(2)),
+                         ("puts(1, \"two\")", Object::Null),
+                         ("type(1)", Object::String("INTEGER".to_owned())),
+                         ("type(len)", Object::String("BUILTIN".to_owned())),
+                         (r#"type({})"#, Object::String("HASH".to_owned())),
+                         ("str(1.5)", Object::String("1.5".to_owned())),
+                         (r#"str("a")"#, Object::String("a".to_owned())),
+                         ("str([1, \"a\"])", Object::String("[1, \"a\"]".to_owned())),
+                         ("int(7)", Object::Integer(7)),
+                         ("int(-2.9)", Object::Integer(-2)),
+                         (r#"int(" 42 ")"#, Object::Integer(42)),
+                         ("int(true)", Object::Integer(1)),
+                         ("int(-9223372036854775808.0)", Object::Integer(i64::MIN)),
+                         ("int(9223372036854774784.0)", Object::Integer(9223372036854774784))];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input));
+        }
+
+        let tests = vec![("range(3)", "[0, 1, 2]"),
+                         ("range(2, 5)", "[2, 3, 4]"),
+                         ("range(5, 0, -2)", "[5, 3, 1]"),
+                         ("range(3, 1)", "[]"),
+                         (r#"keys({"b": 2, "a": 1, 3: 0})"#, r#"[3, "a", "b"]"#),
+                         (r#"values({"b": 2, "a": 1, 3: 0})"#, "[0, 1, 2]")];
+
+        for (input, expected) in tests {
+            assert_eq!(expected, test_eval(input).to_string());
+        }
+
+        assert_eq!("[2, 3]", test_eval("rest([1, 2, 3])").to_string());
+        assert_eq!("[1, 2]", test_eval("push([1], 2)").to_string());
+        assert_eq!("builtin function len", test_eval("len").to_string());
+
+        let program = r#"
+        let map = fn(arr, f) {
+            let iter = fn(arr, accumulated) {
+                if (len(arr) == 0) {
+                    accumulated
+                } else {
+                    iter(rest(arr), push(accumulated, f(first(arr))));
+                }
+            };
+            iter(arr, []);
+        };
+        let reduce = fn(arr, initial, f) {
+            let iter = fn(arr, result) {
+                if (len(arr) == 0) { result } else { iter(rest(arr), f(result, first(arr))) }
+            };
+            iter(arr, initial);
+        };
+        let doubled = map([1, 2, 3, 4], fn(x) { x * 2 });
+        reduce(doubled, 0, fn(sum, x) { sum + x });
+        "#;
+        assert_eq!(Object::Integer(20), test_eval(program));
+    }
+
+    #[test]
+    fn test_builtin_error_kind() {
+        match test_eval("len()") {
+            Object::Error(err) => {
+                assert_eq!(RuntimeErrorKind::WrongArgumentCount {
+                               function: "len".to_owned(),
+                               want: Arity::Exact(1),
+                               got: 0,
+                           },
+                           err.kind)
+            }
+            other => panic!("no error object returned. got={}", other),
+        }
+
+        match test_eval("push(1, 2)") {
+            Object::Error(err) => {
+                assert_eq!(RuntimeErrorKind::ArgumentType {
+                               function: "push",
+                               position: 1,
+                               want: "ARRAY",
+                               got: "INTEGER",
+                           },
+                           err.kind)
+            }
+            other => panic!("no error object returned. got={}", other),
+        }
+    }
+
+    #[test]
+    fn test_limits() {
+        let steps = Limits { max_steps: Some(200), ..Limits::default() };
+        let depth = Limits { max_call_depth: Some(50), ..Limits::default() };
+        let nesting = Limits { max_nesting_depth: Some(20), ..Limits::default() };
+        let size = Limits { max_collection_size: Some(100), ..Limits::default() };
+        let countdown = "let f = fn(n) { if (n == 0) { 0 } else { f(n - 1) } };";
+
+        let tests = vec![("let f = fn(n) { f(n + 1) }; f(0)", steps, Limit::Steps, 200),
+                         ("let f = fn(n) { f(n + 1) }; f(0)", depth, Limit::CallDepth, 50),
+                         ("let f = fn(n) { f(n + 1) }; f(0)", nesting, Limit::NestingDepth, 20),
+                         ("------------------------------1", nesting, Limit::NestingDepth, 20),
+                         ("range(1000000000000)", size, Limit::CollectionSize, 100),
+                         ("push(range(100), 1)", size, Limit::CollectionSize, 100),
+                         ("let s = \"ab\"; s += s; s += s; s += s; s += s; s += s; s += s",
+                          size,
+                          Limit::CollectionSize,
+                          100),
+                         ("let s = \"ab\"; let f = fn(s) { f(s + s) }; f(s)",
+                          size,
+                          Limit::CollectionSize,
+                          100)];
+
+        for (input, limits, limit, max) in tests {
+            match test_eval_with_limits(input, limits) {
+                Object::Error(err) => {
+                    assert_eq!(RuntimeErrorKind::LimitExceeded { limit, max }, err.kind);
+                    assert!(err.span.is_some());
+                }
+                other => panic!("no error object returned. got={}", other),
+            }
+        }
+
+        let tests = vec![(format!("{} f(10)", countdown), steps),
+                         (format!("{} f(40) + f(40) + f(40)", countdown), depth),
+                         (format!("{} f(5) + -------------1", countdown), nesting),
+                         ("len(range(100)) + len(push(range(99), 1))".to_owned(), size)];
+
+        for (input, limits) in tests {
+            assert!(!test_eval_with_limits(&input, limits).is_error(), "{}", input);
+        }
+
+        match test_eval_with_limits("let f = fn() { f() }; f()", steps) {
+            Object::Error(err) => {
+                assert_eq!("evaluation step limit of 200 exceeded", err.to_string())
+            }
+            other => panic!("no error object returned. got={}", other),
+        }
+    }
+
+    #[test]
+    fn test_function_object() {
+        match test_eval("fn(x) { x + 2; };") {
+            Object::Function(function) => {
+                assert_eq!(1, function.parameters.len());
+                assert_eq!("x", function.parameters[0].value);
+                assert_eq!("{ (x + 2) }", function.body.to_string());
+            }
+            other => panic!("object is not Function. got={}", other),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use std::fmt;
+use std::rc::Rc;
+use evaluator::{Object, Arity, RuntimeError};
+
+pub type HostFn = dyn Fn(&[Object]) -> Result<Object, RuntimeError>;
+
+/// A Rust closure registered by the program embedding the interpreter. Unlike
+/// a `Builtin` it can capture state.
+#[derive(Clone)]
+pub struct HostFunction {
+    pub name: String,
+    /// The number of arguments the closure accepts, or `None` to pass it
+    /// whatever the script supplies.
+    pub arity: Option<Arity>,
+    function: Rc<HostFn>,
+}
+
+impl HostFunction {
+    pub fn new<F>(name: &str, arity: Option<Arity>, function: F) -> Self
+        where F: Fn(&[Object]) -> Result<Object, RuntimeError> + 'static
+    {
+        HostFunction {
+            name: name.to_owned(),
+            arity,
+            function: Rc::new(function),
+        }
+    }
+
+    pub fn call(&self, arguments: &[Object]) -> Object {
+        let checked = self.arity.map_or(Ok(()), |arity| arity.check(&self.name, arguments.len()));
+        match checked.and_then(|()| (self.function)(arguments)) {
+            Ok(result) => result,
+            Err(err) => Object::Error(err),
+        }
+    }
+}
+
+impl PartialEq for HostFunction {
+    fn eq(&self, other: &HostFunction) -> bool {
+        Rc::ptr_eq(&self.function, &other.function)
+    }
+}
+
+impl fmt::Debug for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "HostFunction({})", self.name)
+    }
+}
+
+impl fmt::Display for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "builtin function {}", self.name)
+    }
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Bounds on the resources a script may use, for running untrusted code.
+/// `None` means unlimited, which is the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Limits {
+    /// How many expressions may be evaluated in total.
+    pub max_steps: Option<usize>,
+    /// How deeply function calls may nest.
+    pub max_call_depth: Option<usize>,
+    /// How deeply expressions may nest during evaluation, counting those in
+    /// every function call that hasn't returned yet. The evaluator recurses
+    /// once per level, so this is what keeps it from overflowing the native
+    /// stack.
+    pub max_nesting_depth: Option<usize>,
+    /// How many elements an array or hash, or how many bytes a string, that
+    /// the script creates may hold.
+    pub max_collection_size: Option<usize>,
+}
+
+impl Limits {
+    /// No limits at all, for scripts that are trusted not to run away.
+    pub const fn unlimited() -> Self {
+        Limits {
+            max_steps: None,
+            max_call_depth: None,
+            max_nesting_depth: None,
+            max_collection_size: None,
+        }
+    }
+}
+
+/// The resource a `LimitExceeded` error ran out of.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    Steps,
+    CallDepth,
+    NestingDepth,
+    CollectionSize,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Limit::Steps => write!(f, "evaluation step"),
+            Limit::CallDepth => write!(f, "call depth"),
+            Limit::NestingDepth => write!(f, "nesting depth"),
+            Limit::CollectionSize => write!(f, "collection size"),
+        }
+    }
+}
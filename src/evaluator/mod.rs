@@ -0,0 +1,20 @@
+mod object;
+mod runtime_error;
+mod environment;
+mod limits;
+mod cancellation;
+mod context;
+mod evaluator;
+mod builtins;
+mod host_function;
+mod conversion;
+
+pub use self::object::*;
+pub use self::runtime_error::*;
+pub use self::environment::*;
+pub use self::limits::*;
+pub use self::cancellation::*;
+pub use self::context::*;
+pub use self::evaluator::*;
+pub use self::builtins::*;
+pub use self::host_function::*;
@@ -0,0 +1,181 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use ast::{Identifier, BlockStatement};
+use evaluator::{Environment, Builtin, HostFunction, RuntimeError};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Object {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    String(String),
+    Null,
+    ReturnValue(Box<Object>),
+    Error(RuntimeError),
+    Function(Function),
+    Builtin(Builtin),
+    HostFunction(HostFunction),
+    Array(Rc<Vec<Object>>),
+    Hash(Rc<HashMap<HashKey, Object>>),
+}
+
+impl Object {
+    pub fn error(message: String) -> Self {
+        Object::Error(RuntimeError::new(message))
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Object::Integer(_) => "INTEGER",
+            Object::Float(_) => "FLOAT",
+            Object::Boolean(_) => "BOOLEAN",
+            Object::String(_) => "STRING",
+            Object::Null => "NULL",
+            Object::ReturnValue(_) => "RETURN_VALUE",
+            Object::Error(_) => "ERROR",
+            Object::Function(_) => "FUNCTION",
+            Object::Builtin(_) |
+            Object::HostFunction(_) => "BUILTIN",
+            Object::Array(_) => "ARRAY",
+            Object::Hash(_) => "HASH",
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(*self, Object::Error(_))
+    }
+
+    /// Whether evaluation must stop and hand this value up unchanged: a
+    /// `return` on its way out of the function body, or an error.
+    pub fn is_return_or_error(&self) -> bool {
+        matches!(*self, Object::ReturnValue(_) | Object::Error(_))
+    }
+
+    pub fn is_truthy(&self) -> bool {
+        !matches!(*self, Object::Null | Object::Boolean(false))
+    }
+
+    /// The key this value is stored under when used as a hash key, or `None`
+    /// if values of its type can't be hash keys.
+    pub fn hash_key(&self) -> Option<HashKey> {
+        match *self {
+            Object::Integer(value) => Some(HashKey::Integer(value)),
+            Object::Boolean(value) => Some(HashKey::Boolean(value)),
+            Object::String(ref value) => Some(HashKey::String(value.clone())),
+            _ => None,
+        }
+    }
+
+    /// Like `to_string`, but quotes strings, for showing values nested in a
+    /// collection.
+    pub fn inspect(&self) -> String {
+        match *self {
+            Object::String(ref value) => format!("{:?}", value),
+            _ => self.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Object {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Object::Integer(value) => write!(f, "{}", value),
+            Object::Float(value) => write!(f, "{:?}", value),
+            Object::Boolean(value) => write!(f, "{}", value),
+            Object::String(ref value) => write!(f, "{}", value),
+            Object::Null => write!(f, "null"),
+            Object::ReturnValue(ref value) => value.fmt(f),
+            Object::Error(ref err) => write!(f, "ERROR: {}", err),
+            Object::Function(ref function) => function.fmt(f),
+            Object::Builtin(ref builtin) => builtin.fmt(f),
+            Object::HostFunction(ref function) => function.fmt(f),
+            Object::Array(ref elements) => {
+                let elements = elements.iter().map(Object::inspect).collect::<Vec<String>>();
+                write!(f, "[{}]", elements.join(", "))
+            }
+            Object::Hash(ref pairs) => {
+                // Sort by key so that the output doesn't depend on hashing order.
+                let mut pairs = pairs.iter().collect::<Vec<(&HashKey, &Object)>>();
+                pairs.sort_by(|a, b| a.0.cmp(b.0));
+
+                let pairs = pairs.iter()
+                    .map(|&(key, value)| format!("{}: {}", key, value.inspect()))
+                    .collect::<Vec<String>>();
+                write!(f, "{{{}}}", pairs.join(", "))
+            }
+        }
+    }
+}
+
+/// The hashable subset of objects, used as the keys of a `Hash`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HashKey {
+    Integer(i64),
+    Boolean(bool),
+    String(String),
+}
+
+impl From<HashKey> for Object {
+    fn from(key: HashKey) -> Self {
+        match key {
+            HashKey::Integer(value) => Object::Integer(value),
+            HashKey::Boolean(value) => Object::Boolean(value),
+            HashKey::String(value) => Object::String(value),
+        }
+    }
+}
+
+impl fmt::Display for HashKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HashKey::Integer(value) => write!(f, "{}", value),
+            HashKey::Boolean(value) => write!(f, "{}", value),
+            HashKey::String(ref value) => write!(f, "{:?}", value),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Function {
+    pub parameters: Vec<Identifier>,
+    pub body: Rc<BlockStatement>,
+    pub env: Rc<RefCell<Environment>>,
+}
+
+impl Function {
+    pub fn new(parameters: Vec<Identifier>,
+               body: Rc<BlockStatement>,
+               env: Rc<RefCell<Environment>>)
+               -> Self {
+        Function {
+            parameters,
+            body,
+            env,
+        }
+    }
+}
+
+impl PartialEq for Function {
+    fn eq(&self, other: &Function) -> bool {
+        Rc::ptr_eq(&self.body, &other.body) && Rc::ptr_eq(&self.env, &other.env)
+    }
+}
+
+impl fmt::Debug for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Function({})", self)
+    }
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parameters = self.parameters
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>();
+
+        write!(f, "fn({}) {}", parameters.join(", "), self.body)
+    }
+}
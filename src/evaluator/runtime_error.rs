@@ -0,0 +1,118 @@
+use std::error::Error;
+use std::fmt;
+use token::Span;
+use evaluator::{Arity, Limit, Interrupt};
+
+/// An error raised while evaluating a program, carried by `Object::Error`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuntimeError {
+    pub kind: RuntimeErrorKind,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeErrorKind {
+    /// An error without a dedicated kind, described by its message.
+    Other(String),
+    WrongArgumentCount {
+        function: String,
+        want: Arity,
+        got: usize,
+    },
+    /// `position` is 1-based.
+    ArgumentType {
+        function: &'static str,
+        position: usize,
+        want: &'static str,
+        got: &'static str,
+    },
+    /// The script used more of a resource than `Limits` allows.
+    LimitExceeded { limit: Limit, max: usize },
+    /// The host cancelled the evaluation or its deadline passed.
+    Interrupted { reason: Interrupt },
+    /// A value passed to or returned from the host had the wrong type.
+    UnexpectedType {
+        want: &'static str,
+        got: &'static str,
+    },
+}
+
+impl RuntimeError {
+    pub fn new(message: String) -> Self {
+        RuntimeError::from_kind(RuntimeErrorKind::Other(message))
+    }
+
+    pub fn wrong_argument_count(function: &str, want: Arity, got: usize) -> Self {
+        RuntimeError::from_kind(RuntimeErrorKind::WrongArgumentCount {
+                                    function: function.to_owned(),
+                                    want,
+                                    got,
+                                })
+    }
+
+    pub fn argument_type(function: &'static str,
+                         position: usize,
+                         want: &'static str,
+                         got: &'static str)
+                         -> Self {
+        RuntimeError::from_kind(RuntimeErrorKind::ArgumentType {
+                                    function,
+                                    position,
+                                    want,
+                                    got,
+                                })
+    }
+
+    pub fn limit_exceeded(limit: Limit, max: usize) -> Self {
+        RuntimeError::from_kind(RuntimeErrorKind::LimitExceeded { limit, max })
+    }
+
+    pub fn interrupted(reason: Interrupt) -> Self {
+        RuntimeError::from_kind(RuntimeErrorKind::Interrupted { reason })
+    }
+
+    pub fn unexpected_type(want: &'static str, got: &'static str) -> Self {
+        RuntimeError::from_kind(RuntimeErrorKind::UnexpectedType { want, got })
+    }
+
+    fn from_kind(kind: RuntimeErrorKind) -> Self {
+        RuntimeError { kind, span: None }
+    }
+
+    /// Attaches `span` unless the error already points somewhere more precise.
+    pub fn or_span(self, span: Span) -> Self {
+        RuntimeError { span: self.span.or(Some(span)), ..self }
+    }
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            RuntimeErrorKind::Other(ref message) => write!(f, "{}", message),
+            RuntimeErrorKind::WrongArgumentCount { ref function, want, got } => {
+                write!(f,
+                       "wrong number of arguments to `{}`: want={}, got={}",
+                       function,
+                       want,
+                       got)
+            }
+            RuntimeErrorKind::ArgumentType { function, position, want, got } => {
+                write!(f,
+                       "argument {} to `{}` must be {}, got {}",
+                       position,
+                       function,
+                       want,
+                       got)
+            }
+            RuntimeErrorKind::LimitExceeded { limit, max } => {
+                write!(f, "{} limit of {} exceeded", limit, max)
+            }
+            RuntimeErrorKind::Interrupted { reason } => reason.fmt(f),
+            RuntimeErrorKind::UnexpectedType { want, got } => {
+                write!(f, "expected {}, got {}", want, got)
+            }
+        }
+    }
+}
+
+impl Error for RuntimeError {}
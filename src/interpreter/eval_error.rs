@@ -0,0 +1,32 @@
+use std::error::Error;
+use std::fmt;
+use parser::ParseError;
+use evaluator::RuntimeError;
+
+/// Why `Interpreter::eval_str` failed: the source didn't parse, or evaluating
+/// it raised an error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    Parse(Vec<ParseError>),
+    Runtime(RuntimeError),
+}
+
+impl From<RuntimeError> for EvalError {
+    fn from(err: RuntimeError) -> Self {
+        EvalError::Runtime(err)
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            EvalError::Parse(ref errors) => {
+                let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<String>>();
+                write!(f, "{}", errors.join("\n"))
+            }
+            EvalError::Runtime(ref err) => err.fmt(f),
+        }
+    }
+}
+
+impl Error for EvalError {}
@@ -0,0 +1,395 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use lexer::Lexer;
+use parser::Parser;
+use evaluator::{eval_with_context, apply_function, lookup_builtin, Environment, Context, Limits,
+                CancellationToken, Arity, HostFunction, Object, RuntimeError};
+use interpreter::EvalError;
+
+/// The limits an `Interpreter` starts with. They keep a runaway script from
+/// overflowing a 2 MB stack, the default for spawned threads, even in a debug
+/// build.
+pub const DEFAULT_LIMITS: Limits = Limits {
+    max_steps: None,
+    max_call_depth: Some(100),
+    max_nesting_depth: Some(256),
+    max_collection_size: None,
+};
+
+/// Embeds Monkey in a Rust program: evaluates source against a global
+/// environment that persists between calls and that the host can read,
+/// write and extend with its own functions.
+#[derive(Debug)]
+pub struct Interpreter {
+    env: Rc<RefCell<Environment>>,
+    limits: Limits,
+    cancellation: CancellationToken,
+    timeout: Option<Duration>,
+}
+
+impl Interpreter {
+    pub fn new() -> Self {
+        Interpreter {
+            env: Rc::new(RefCell::new(Environment::new())),
+            limits: DEFAULT_LIMITS,
+            cancellation: CancellationToken::new(),
+            timeout: None,
+        }
+    }
+
+    /// Applies `limits` instead of `DEFAULT_LIMITS` to every later `eval_str`
+    /// and `call_function`. Each call gets the full budget; nothing carries
+    /// over between calls. `Limits::unlimited()` turns them all off.
+    pub fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Interrupts every later `eval_str` and `call_function` that runs for
+    /// longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Watches `token` instead of the interpreter's own, for example to
+    /// cancel every interpreter serving a request at once.
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Returns a handle that can be sent to another thread to interrupt the
+    /// running evaluation. A cancelled token stays cancelled, failing every
+    /// later call, until `reset_cancellation` is called.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
+    /// Lets evaluation run again after a cancellation. Globals defined before
+    /// the cancellation are kept.
+    pub fn reset_cancellation(&mut self) {
+        self.cancellation.reset();
+    }
+
+    /// Binds `name` to a Rust closure that scripts can call like any other
+    /// function, with any number of arguments. An `Err` returned by the
+    /// closure becomes a runtime error at the call site.
+    pub fn register_fn<F>(&mut self, name: &str, function: F)
+        where F: Fn(&[Object]) -> Result<Object, RuntimeError> + 'static
+    {
+        let function = HostFunction::new(name, None, function);
+        self.set_global(name, Object::HostFunction(function));
+    }
+
+    /// Like `register_fn`, but a call with a number of arguments that `arity`
+    /// doesn't accept fails without reaching the closure.
+    pub fn register_fn_with_arity<F>(&mut self, name: &str, arity: Arity, function: F)
+        where F: Fn(&[Object]) -> Result<Object, RuntimeError> + 'static
+    {
+        let function = HostFunction::new(name, Some(arity), function);
+        self.set_global(name, Object::HostFunction(function));
+    }
+
+    pub fn set_global<T: Into<Object>>(&mut self, name: &str, value: T) {
+        self.env.borrow_mut().set(name, value.into());
+    }
+
+    pub fn get_global(&self, name: &str) -> Option<Object> {
+        self.env.borrow().get(name)
+    }
+
+    /// Parses and evaluates `source`, returning the value of its last
+    /// statement. Bindings made by the script stay visible to later calls.
+    pub fn eval_str(&mut self, source: &str) -> Result<Object, EvalError> {
+        let mut parser = Parser::new(Lexer::new(source));
+        let program = parser.parser_program().map_err(EvalError::Parse)?;
+
+        match eval_with_context(&program, &self.env, &self.context()) {
+            Object::Error(err) => Err(EvalError::Runtime(err)),
+            result => Ok(result),
+        }
+    }
+
+    /// Calls the global or builtin function `name` with `arguments`.
+    pub fn call_function(&mut self,
+                         name: &str,
+                         arguments: Vec<Object>)
+                         -> Result<Object, RuntimeError> {
+        let function = self.get_global(name).or_else(|| lookup_builtin(name).map(Object::Builtin));
+        let function = match function {
+            Some(function) => function,
+            None => return Err(RuntimeError::new(format!("identifier not found: {}", name))),
+        };
+
+        match apply_function(function, arguments, &self.context()) {
+            Object::Error(err) => Err(err),
+            result => Ok(result),
+        }
+    }
+
+    fn context(&self) -> Context {
+        let ctx = Context::new(self.limits).with_cancellation(self.cancellation.clone());
+        match self.timeout {
+            Some(timeout) => ctx.with_deadline(Instant::now() + timeout),
+            None => ctx,
+        }
+    }
+}
+
+impl Default for Interpreter {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::cell::Cell;
+    use std::collections::HashMap;
+    use std::convert::TryFrom;
+    use std::rc::Rc;
+    use std::thread;
+    use std::time::Duration;
+    use evaluator::{Object, RuntimeError, RuntimeErrorKind, Arity, Limits, Limit, Interrupt,
+                    CancellationToken};
+    use interpreter::{Interpreter, EvalError, DEFAULT_LIMITS};
+
+    fn expect_runtime_error(result: Result<Object, EvalError>) -> RuntimeError {
+        match result {
+            Err(EvalError::Runtime(err)) => err,
+            other => panic!("expected a runtime error. got={:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_eval_str_keeps_globals() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(Ok(Object::Null), interpreter.eval_str("let x = 20;"));
+        assert_eq!(Ok(Object::Integer(22)), interpreter.eval_str("x + 2"));
+        assert_eq!(Some(Object::Integer(20)), interpreter.get_global("x"));
+        assert_eq!(None, interpreter.get_global("y"));
+    }
+
+    #[test]
+    fn test_eval_str_errors() {
+        let mut interpreter = Interpreter::new();
+
+        match interpreter.eval_str("let = 1;") {
+            Err(EvalError::Parse(errors)) => assert!(!errors.is_empty()),
+            other => panic!("expected a parse error. got={:?}", other),
+        }
+        let err = expect_runtime_error(interpreter.eval_str("1 + true"));
+        assert_eq!("type mismatch: INTEGER + BOOLEAN", err.to_string());
+    }
+
+    #[test]
+    fn test_set_global() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_global("name", "monkey");
+        interpreter.set_global("scores", vec![1, 2, 3]);
+        interpreter.set_global("ratio", 0.5);
+
+        assert_eq!(Ok(Object::String("monkey!".to_owned())),
+                   interpreter.eval_str(r#"name + "!""#));
+        assert_eq!(Ok(Object::Integer(3)), interpreter.eval_str("len(scores)"));
+        assert_eq!(Ok(Object::Float(1.5)), interpreter.eval_str("ratio * scores[2]"));
+    }
+
+    #[test]
+    fn test_register_fn() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+
+        let mut interpreter = Interpreter::new();
+        interpreter.register_fn_with_arity("add", Arity::Exact(2), move |args: &[Object]| {
+            counter.set(counter.get() + 1);
+            let a = i64::try_from(args[0].clone())?;
+            let b = i64::try_from(args[1].clone())?;
+            Ok(Object::from(a + b))
+        });
+
+        assert_eq!(Ok(Object::Integer(5)), interpreter.eval_str("add(2, 3)"));
+        assert_eq!(Ok(Object::String("BUILTIN".to_owned())), interpreter.eval_str("type(add)"));
+        assert_eq!("builtin function add",
+                   interpreter.get_global("add").unwrap().to_string());
+
+        let err = expect_runtime_error(interpreter.eval_str(r#"add(1, "2")"#));
+        assert_eq!(RuntimeErrorKind::UnexpectedType {
+                       want: "INTEGER",
+                       got: "STRING",
+                   },
+                   err.kind);
+        assert_eq!("expected INTEGER, got STRING", err.to_string());
+        assert!(err.span.is_some());
+
+        for input in &["add()", "add(1)", "add(1, 2, 3)"] {
+            match expect_runtime_error(interpreter.eval_str(input)).kind {
+                RuntimeErrorKind::WrongArgumentCount { ref function, want, .. } => {
+                    assert_eq!("add", function);
+                    assert_eq!(Arity::Exact(2), want);
+                }
+                ref other => panic!("wrong error kind. got={:?}", other),
+            }
+        }
+        assert_eq!("wrong number of arguments to `add`: want=2, got=0",
+                   interpreter.call_function("add", vec![]).unwrap_err().to_string());
+
+        assert_eq!(2, calls.get());
+
+        interpreter.register_fn("sum", |args: &[Object]| {
+            let mut total = 0;
+            for arg in args {
+                total += i64::try_from(arg.clone())?;
+            }
+            Ok(Object::from(total))
+        });
+        assert_eq!(Ok(Object::Integer(0)), interpreter.eval_str("sum()"));
+        assert_eq!(Ok(Object::Integer(6)), interpreter.eval_str("sum(1, 2, 3)"));
+    }
+
+    #[test]
+    fn test_call_function() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let greet = fn(name) { \"hello \" + name };").unwrap();
+
+        assert_eq!(Ok(Object::String("hello rust".to_owned())),
+                   interpreter.call_function("greet", vec![Object::from("rust")]));
+        assert_eq!(Ok(Object::Integer(2)),
+                   interpreter.call_function("len", vec![Object::from(vec![1, 2])]));
+        assert_eq!(Err(RuntimeError::new("identifier not found: missing".to_owned())),
+                   interpreter.call_function("missing", vec![]));
+        assert_eq!("wrong number of arguments: want=1, got=0",
+                   interpreter.call_function("greet", vec![]).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut interpreter = Interpreter::new();
+        let mut ages = HashMap::new();
+        ages.insert("ann", 31);
+        ages.insert("bob", 27);
+        interpreter.set_global("ages", ages);
+        interpreter.set_global("nothing", None::<i64>);
+
+        let result = interpreter.eval_str(r#"ages["ann"] + ages["bob"]"#).unwrap();
+        assert_eq!(Ok(58), i64::try_from(result));
+        assert_eq!(Ok(Object::Null), interpreter.eval_str("nothing"));
+
+        let result = interpreter.eval_str(r#"{"a": [1, 2], "b": []}"#).unwrap();
+        let map = HashMap::<String, Vec<i64>>::try_from(result).unwrap();
+        assert_eq!(vec![1, 2], map["a"]);
+        assert!(map["b"].is_empty());
+
+        assert_eq!(Ok(2.0), f64::try_from(Object::Integer(2)));
+        assert_eq!(Ok(true), bool::try_from(Object::Boolean(true)));
+        assert_eq!(Ok("a".to_owned()), String::try_from(Object::from("a")));
+        assert_eq!(Err(RuntimeError::unexpected_type("ARRAY", "INTEGER")),
+                   Vec::<i64>::try_from(Object::Integer(1)));
+        assert_eq!(Err(RuntimeError::unexpected_type("INTEGER", "STRING")),
+                   Vec::<i64>::try_from(Object::from(vec!["a"])));
+    }
+
+    #[test]
+    fn test_limits() {
+        let limits = Limits { max_steps: Some(100), ..Limits::default() };
+        let mut interpreter = Interpreter::new().with_limits(limits);
+        interpreter.eval_str("let loop = fn(n) { loop(n + 1) };").unwrap();
+
+        let exceeded = RuntimeErrorKind::LimitExceeded {
+            limit: Limit::Steps,
+            max: 100,
+        };
+        assert_eq!(exceeded, expect_runtime_error(interpreter.eval_str("loop(0)")).kind);
+        assert_eq!(exceeded,
+                   interpreter.call_function("loop", vec![Object::from(0)]).unwrap_err().kind);
+
+        // Every call starts with a fresh budget.
+        assert_eq!(Ok(Object::Integer(3)), interpreter.eval_str("1 + 2"));
+    }
+
+    #[test]
+    fn test_default_limits() {
+        let mut interpreter = Interpreter::new();
+        let err = expect_runtime_error(interpreter.eval_str("let f = fn(n) { f(n + 1) }; f(0)"));
+        assert_eq!(RuntimeErrorKind::LimitExceeded {
+                       limit: Limit::CallDepth,
+                       max: DEFAULT_LIMITS.max_call_depth.unwrap(),
+                   },
+                   err.kind);
+
+        let chain = format!("0{}", " + 1".repeat(300));
+        let err = expect_runtime_error(interpreter.eval_str(&chain));
+        assert_eq!(RuntimeErrorKind::LimitExceeded {
+                       limit: Limit::NestingDepth,
+                       max: DEFAULT_LIMITS.max_nesting_depth.unwrap(),
+                   },
+                   err.kind);
+
+        let mut interpreter = Interpreter::new().with_limits(Limits::unlimited());
+        assert_eq!(Ok(Object::Integer(300)), interpreter.eval_str(&chain));
+    }
+
+    // Doubles the work for every level but only recurses `n` deep, so it
+    // runs for a long time without overflowing the stack.
+    const SLOW: &str = "let slow = fn(n) { if (n == 0) { 0 } else { slow(n - 1) + slow(n - 1) } };";
+
+    #[test]
+    fn test_cancellation() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str(SLOW).unwrap();
+
+        let token = interpreter.cancellation_token();
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(20));
+            token.cancel();
+        });
+
+        let interrupted = RuntimeErrorKind::Interrupted { reason: Interrupt::Cancelled };
+        let err = expect_runtime_error(interpreter.eval_str("slow(64)"));
+        assert_eq!(interrupted, err.kind);
+        assert_eq!("evaluation was cancelled", err.to_string());
+        canceller.join().unwrap();
+
+        assert!(interpreter.cancellation_token().is_cancelled());
+        assert_eq!(interrupted,
+                   interpreter.call_function("slow", vec![Object::from(1)]).unwrap_err().kind);
+
+        interpreter.reset_cancellation();
+        assert!(!interpreter.cancellation_token().is_cancelled());
+        assert_eq!(Ok(Object::Integer(0)), interpreter.eval_str("slow(4)"));
+        assert_eq!(Ok(Object::Integer(0)),
+                   interpreter.call_function("slow", vec![Object::from(1)]));
+    }
+
+    #[test]
+    fn test_shared_cancellation_token() {
+        let token = CancellationToken::new();
+        let mut first = Interpreter::new().with_cancellation_token(token.clone());
+        let mut second = Interpreter::new().with_cancellation_token(token.clone());
+        first.eval_str("let x = 1;").unwrap();
+
+        token.cancel();
+        let interrupted = RuntimeErrorKind::Interrupted { reason: Interrupt::Cancelled };
+        for interpreter in &mut [&mut first, &mut second] {
+            assert_eq!(interrupted, expect_runtime_error(interpreter.eval_str("1 + 1")).kind);
+        }
+
+        token.reset();
+        assert_eq!(Ok(Object::Integer(2)), first.eval_str("x + 1"));
+        assert_eq!(Ok(Object::Integer(2)), second.eval_str("1 + 1"));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let mut interpreter = Interpreter::new().with_timeout(Duration::from_millis(20));
+        interpreter.eval_str(SLOW).unwrap();
+
+        assert_eq!(RuntimeErrorKind::Interrupted { reason: Interrupt::DeadlineExceeded },
+                   expect_runtime_error(interpreter.eval_str("slow(64)")).kind);
+
+        // The deadline is measured from the start of each call.
+        assert_eq!(Ok(Object::Integer(0)), interpreter.eval_str("slow(4)"));
+    }
+}
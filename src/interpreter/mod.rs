@@ -0,0 +1,5 @@
+mod eval_error;
+mod interpreter;
+
+pub use self::eval_error::*;
+pub use self::interpreter::*;
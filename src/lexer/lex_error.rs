@@ -0,0 +1,42 @@
+use std::error::Error;
+use std::fmt;
+use token::Span;
+
+/// A character sequence that isn't a valid Monkey token.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexError {
+    pub kind: LexErrorKind,
+    pub literal: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LexErrorKind {
+    IllegalCharacter,
+    UnterminatedComment,
+}
+
+impl LexError {
+    pub fn new(kind: LexErrorKind, literal: String, span: Span) -> Self {
+        LexError {
+            kind,
+            literal,
+            span,
+        }
+    }
+}
+
+impl fmt::Display for LexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            LexErrorKind::IllegalCharacter => {
+                write!(f, "illegal character `{}` at {}", self.literal, self.span)
+            }
+            LexErrorKind::UnterminatedComment => {
+                write!(f, "unterminated block comment at {}", self.span)
+            }
+        }
+    }
+}
+
+impl Error for LexError {}
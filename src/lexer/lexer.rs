@@ -1,20 +1,33 @@
+use unicode_xid::UnicodeXID;
 use token::*;
+use lexer::{LexError, LexErrorKind};
 
+/// Splits Monkey source into tokens. Token literals borrow from the input,
+/// so lexing doesn't allocate.
 #[derive(Debug, Clone)]
-pub struct Lexer {
-    input: String,
+pub struct Lexer<'src> {
+    input: &'src str,
+    // Byte offset of `ch` and of the character after it.
     position: usize,
     read_position: usize,
     ch: Option<char>,
+    line: usize,
+    column: usize,
+    finished: bool,
+    emit_comments: bool,
 }
 
-impl Lexer {
-    pub fn new(input: String) -> Lexer {
+impl<'src> Lexer<'src> {
+    pub fn new(input: &'src str) -> Self {
         let mut l = Lexer {
-            input: input,
+            input,
             position: 0,
             read_position: 0,
             ch: None,
+            line: 1,
+            column: 1,
+            finished: false,
+            emit_comments: false,
         };
 
         l.read_char();
@@ -22,128 +35,261 @@ impl Lexer {
     }
 
     pub fn read_char(&mut self) {
-        let chars = self.input.chars().collect::<Vec<char>>();
-        if chars.len() > self.read_position {
-            self.ch = Some(chars[self.read_position]);
-        } else {
-            self.ch = None;
+        if let Some(ch) = self.ch {
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
         }
 
         self.position = self.read_position;
-        self.read_position += 1;
+        self.ch = self.input[self.read_position..].chars().next();
+        if let Some(ch) = self.ch {
+            self.read_position += ch.len_utf8();
+        }
+    }
+
+    /// Makes the lexer emit comments as `LineComment` and `BlockComment`
+    /// tokens instead of skipping them, for tools that need to retain them.
+    pub fn with_comments(mut self) -> Self {
+        self.emit_comments = true;
+        self
+    }
+
+    pub fn next_token(&mut self) -> Token<'src> {
+        loop {
+            let token = self.read_token();
+            if self.emit_comments || !token.token_type.is_trivia() {
+                return token;
+            }
+        }
     }
 
-    pub fn next_token(&mut self) -> Token {
+    fn read_token(&mut self) -> Token<'src> {
         self.skip_whitespace();
 
-        if let Some(c) = self.ch {
-            let token = match c {
-                '+' | '-' | ';' | '(' | ')' | ',' | '{' | '}' | '/' | '*' | '<' | '>' | '=' |
-                '!' => {
-                    let literal = if c == '=' || c == '!' {
-                        self.get_equal_or_not_equal_string(&c).unwrap_or(c.to_string())
-                    } else {
-                        c.to_string()
-                    };
-
-                    let token_type = literal.parse::<TokenType>().unwrap_or(TokenType::EOF);
+        let (start, line, column) = (self.position, self.line, self.column);
+
+        let c = match self.ch {
+            Some(c) => c,
+            None => {
+                return Token::new(TokenType::EOF,
+                                  &self.input[start..],
+                                  Span::new(start, start, line, column))
+            }
+        };
+
+        let token_type = match c {
+            '/' if self.peek_char() == Some('/') => {
+                self.read_line_comment();
+                TokenType::LineComment
+            }
+            '/' if self.peek_char() == Some('*') => self.read_block_comment(),
+            '+' | '-' | ';' | ':' | '(' | ')' | ',' | '{' | '}' | '[' | ']' | '/' | '*' | '%' |
+            '<' | '>' | '=' | '!' | '&' | '|' => {
+                // Operators are at most two characters long; take the longest match.
+                self.read_char();
+                if self.ch.is_some() &&
+                   self.input[start..self.read_position].parse::<TokenType>().is_ok() {
                     self.read_char();
-                    Token::new(token_type, literal)
                 }
-                _ => {
-                    if self.is_letter() {
-                        let literal = self.read_identifier();
-                        let token_type = TokenType::lookup_indent(&literal);
-
-                        Token::new(token_type, literal)
-                    } else if self.is_digit() {
-                        Token::new(TokenType::Int, self.read_number())
-                    } else {
-                        Token::new(TokenType::Illegal, c.to_string())
-                    }
+
+                self.slice_input(start).parse::<TokenType>().unwrap_or(TokenType::Illegal)
+            }
+            '"' => {
+                self.read_string();
+                TokenType::String
+            }
+            _ => {
+                if self.is_identifier_start() {
+                    TokenType::lookup_indent(self.read_identifier())
+                } else if self.is_digit() {
+                    self.read_number()
+                } else {
+                    self.read_char();
+                    TokenType::Illegal
                 }
-            };
+            }
+        };
 
-            return token;
-        }
+        Token::new(token_type,
+                   self.slice_input(start),
+                   Span::new(start, self.position, line, column))
+    }
 
-        self.read_char();
-        Token::new(TokenType::EOF, "".to_owned())
+    /// Identifiers follow Unicode's XID_Start and XID_Continue properties,
+    /// and may additionally start with an underscore.
+    fn is_identifier_start(&self) -> bool {
+        if let Some(ch) = self.ch {
+            return ch.is_ascii_alphabetic() || ch == '_' ||
+                   (!ch.is_ascii() && UnicodeXID::is_xid_start(ch));
+        }
+        false
     }
 
-    fn is_letter(&self) -> bool {
+    fn is_identifier_continue(&self) -> bool {
         if let Some(ch) = self.ch {
-            return ('a' <= ch && ch <= 'z') || ('A' <= ch && ch <= 'Z') || ch == '_';
+            return ch.is_ascii_alphanumeric() || ch == '_' ||
+                   (!ch.is_ascii() && UnicodeXID::is_xid_continue(ch));
         }
         false
     }
 
     fn is_digit(&self) -> bool {
         if let Some(ch) = self.ch {
-            return '0' <= ch && ch <= '9';
+            return ch.is_ascii_digit();
         }
         false
     }
 
-    fn read_identifier(&mut self) -> String {
+    fn read_identifier(&mut self) -> &'src str {
         let position = self.position;
-        while self.is_letter() {
+        self.read_char();
+        while self.is_identifier_continue() {
             self.read_char();
         }
 
         self.slice_input(position)
     }
 
-    fn read_number(&mut self) -> String {
-        let position = self.position;
-        while self.is_digit() {
+    /// Consumes a numeric literal, returning `Float` if it has a fraction or an
+    /// exponent. Letters and digits directly following the number are made
+    /// part of it, so that the parser reports `0x`, `0b12` or `1.2.3` as a
+    /// whole instead of splitting them into several tokens.
+    fn read_number(&mut self) -> TokenType {
+        let prefixed = self.ch == Some('0') &&
+                       self.peek_char().is_some_and(|ch| "xXbBoO".contains(ch));
+        let mut token_type = TokenType::Int;
+
+        loop {
+            match self.ch {
+                Some('.') if !prefixed &&
+                             self.peek_char().is_some_and(|ch| ch.is_ascii_digit()) => {
+                    token_type = TokenType::Float;
+                }
+                Some('e') | Some('E') if !prefixed => {
+                    token_type = TokenType::Float;
+                    if self.peek_char() == Some('+') || self.peek_char() == Some('-') {
+                        self.read_char();
+                    }
+                }
+                Some(_) if self.is_identifier_continue() => {}
+                _ => break,
+            }
             self.read_char();
         }
 
-        self.slice_input(position)
+        token_type
     }
 
-    fn skip_whitespace(&mut self) {
+    /// Consumes a string literal including its quotes. Escape sequences are
+    /// only skipped over here; the parser decodes them. An unterminated string
+    /// runs to the end of the input.
+    fn read_string(&mut self) {
+        self.read_char();
+
         while let Some(ch) = self.ch {
-            if vec![' ', '\t', '\n', '\r'].contains(&ch) {
-                self.read_char();
-            } else {
+            self.read_char();
+            match ch {
+                '"' => break,
+                '\\' => self.read_char(),
+                _ => {}
+            }
+        }
+    }
+
+    fn read_line_comment(&mut self) {
+        while let Some(ch) = self.ch {
+            if ch == '\n' {
                 break;
             }
+            self.read_char();
         }
     }
 
-    fn peek_char(&self) -> Option<char> {
-        let chars = self.input.chars().collect::<Vec<char>>();
-        if self.read_position > chars.len() {
-            None
-        } else {
-            Some(chars[self.read_position])
+    /// Consumes a block comment, which may contain nested block comments.
+    /// Returns `UnterminatedComment` if the input ends before it is closed.
+    fn read_block_comment(&mut self) -> TokenType {
+        let mut depth = 0;
+
+        while let Some(ch) = self.ch {
+            if ch == '/' && self.peek_char() == Some('*') {
+                depth += 1;
+                self.read_char();
+            } else if ch == '*' && self.peek_char() == Some('/') {
+                depth -= 1;
+                self.read_char();
+            }
+            self.read_char();
+
+            if depth == 0 {
+                return TokenType::BlockComment;
+            }
         }
+
+        TokenType::UnterminatedComment
     }
 
-    fn get_equal_or_not_equal_string(&mut self, c: &char) -> Option<String> {
-        if let Some(next_char) = self.peek_char() {
-            if next_char == '=' {
+    fn skip_whitespace(&mut self) {
+        while let Some(ch) = self.ch {
+            if [' ', '\t', '\n', '\r'].contains(&ch) {
                 self.read_char();
-                return Some(c.to_string() + self.ch.unwrap().to_string().as_str());
+            } else {
+                break;
             }
         }
-        None
     }
 
-    fn slice_input(&self, position: usize) -> String {
-        self.input
-            .chars()
-            .collect::<Vec<char>>()
-            .get(position..self.position)
-            .unwrap()
-            .into_iter()
-            .map(|c| c.clone())
-            .collect()
+    fn peek_char(&self) -> Option<char> {
+        self.input[self.read_position..].chars().next()
+    }
+
+    fn slice_input(&self, position: usize) -> &'src str {
+        &self.input[position..self.position]
+    }
+}
+
+/// Yields every token of the input, the last one being `TokenType::EOF`.
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Token<'src>;
+
+    fn next(&mut self) -> Option<Token<'src>> {
+        if self.finished {
+            return None;
+        }
+
+        let token = self.next_token();
+        if token.token_type == TokenType::EOF {
+            self.finished = true;
+        }
+        Some(token)
     }
 }
 
+/// Lexes `input` into a token stream ending with `TokenType::EOF`, failing on
+/// the first illegal character or unterminated block comment.
+pub fn tokenize<'src>(input: &'src str) -> Result<Vec<Token<'src>>, LexError> {
+    Lexer::new(input)
+        .map(|token| {
+            match token.token_type {
+                TokenType::Illegal => {
+                    Err(LexError::new(LexErrorKind::IllegalCharacter,
+                                      token.literal.to_owned(),
+                                      token.span))
+                }
+                TokenType::UnterminatedComment => {
+                    Err(LexError::new(LexErrorKind::UnterminatedComment,
+                                      token.literal.to_owned(),
+                                      token.span))
+                }
+                _ => Ok(token),
+            }
+        })
+        .collect()
+}
+
 #[test]
 fn test_next_token() {
     let input = r#"
@@ -155,7 +301,7 @@ fn test_next_token() {
     };
 
     let result = add(five, ten);
-    !-/*5;
+    !-/ *5;
     5 < 10 > 5;
 
     if (5 < 10) {
@@ -165,8 +311,11 @@ fn test_next_token() {
     }
     10 == 10;
     10 != 9;
-    "#
-        .to_owned();
+    "foobar"
+    "foo bar"
+    [1, 2];
+    {"foo": "bar"}
+    "#;
 
     let tests = vec![("let", TokenType::Let),
                      ("five", TokenType::Ident),
@@ -241,12 +390,255 @@ fn test_next_token() {
                      ("!=", TokenType::NotEQ),
                      ("9", TokenType::Int),
                      (";", TokenType::Semicolon),
+                     ("\"foobar\"", TokenType::String),
+                     ("\"foo bar\"", TokenType::String),
+                     ("[", TokenType::LBracket),
+                     ("1", TokenType::Int),
+                     (",", TokenType::Comma),
+                     ("2", TokenType::Int),
+                     ("]", TokenType::RBracket),
+                     (";", TokenType::Semicolon),
+                     ("{", TokenType::LBrace),
+                     ("\"foo\"", TokenType::String),
+                     (":", TokenType::Colon),
+                     ("\"bar\"", TokenType::String),
+                     ("}", TokenType::RBrace),
                      ("", TokenType::EOF)];
 
     let mut lexer = Lexer::new(input);
     for (literal, token_type) in tests {
         let token = lexer.next_token();
-        assert_eq!(literal.to_owned(), token.literal);
+        assert_eq!(literal, token.literal);
         assert_eq!(token_type, token.token_type);
     }
 }
+
+#[test]
+fn test_token_span() {
+    let input = "let x = 10;\n  x != 5;\n\"";
+
+    let tests = vec![("let", Span::new(0, 3, 1, 1)),
+                     ("x", Span::new(4, 5, 1, 5)),
+                     ("=", Span::new(6, 7, 1, 7)),
+                     ("10", Span::new(8, 10, 1, 9)),
+                     (";", Span::new(10, 11, 1, 11)),
+                     ("x", Span::new(14, 15, 2, 3)),
+                     ("!=", Span::new(16, 18, 2, 5)),
+                     ("5", Span::new(19, 20, 2, 8)),
+                     (";", Span::new(20, 21, 2, 9)),
+                     ("\"", Span::new(22, 23, 3, 1)),
+                     ("", Span::new(23, 23, 3, 2))];
+
+    let mut lexer = Lexer::new(input);
+    for (literal, span) in tests {
+        let token = lexer.next_token();
+        assert_eq!(literal, token.literal);
+        assert_eq!(span, token.span);
+    }
+}
+
+#[test]
+fn test_string() {
+    let tests = vec![(r#""""#, r#""""#),
+                     (r#""a\"b" c"#, r#""a\"b""#),
+                     (r#""a\\" b"#, r#""a\\""#),
+                     ("\"line\nbreak\"", "\"line\nbreak\""),
+                     (r#""unterminated"#, r#""unterminated"#),
+                     (r#""ends with \"#, r#""ends with \"#)];
+
+    for (input, expected) in tests {
+        let token = Lexer::new(input).next_token();
+        assert_eq!(TokenType::String, token.token_type);
+        assert_eq!(expected, token.literal);
+    }
+}
+
+#[test]
+fn test_number() {
+    let input = "0 42 1_000_000 3.14 1e-9 2.5E+3 0xFF 0b1010 0o755 0x 1.2.3 0b102 7.method 1..2";
+
+    let tokens = Lexer::new(input)
+        .map(|t| (t.token_type, t.literal))
+        .collect::<Vec<(TokenType, &str)>>();
+    assert_eq!(vec![(TokenType::Int, "0"),
+                    (TokenType::Int, "42"),
+                    (TokenType::Int, "1_000_000"),
+                    (TokenType::Float, "3.14"),
+                    (TokenType::Float, "1e-9"),
+                    (TokenType::Float, "2.5E+3"),
+                    (TokenType::Int, "0xFF"),
+                    (TokenType::Int, "0b1010"),
+                    (TokenType::Int, "0o755"),
+                    (TokenType::Int, "0x"),
+                    (TokenType::Float, "1.2.3"),
+                    (TokenType::Int, "0b102"),
+                    (TokenType::Int, "7"),
+                    (TokenType::Illegal, "."),
+                    (TokenType::Ident, "method"),
+                    (TokenType::Int, "1"),
+                    (TokenType::Illegal, "."),
+                    (TokenType::Illegal, "."),
+                    (TokenType::Int, "2"),
+                    (TokenType::EOF, "")],
+               tokens);
+}
+
+#[test]
+fn test_identifier() {
+    let input = "x1 _tmp2 café 変数 δx_0 a·b 1a";
+
+    let tokens = Lexer::new(input)
+        .map(|t| (t.token_type, t.literal))
+        .collect::<Vec<(TokenType, &str)>>();
+    assert_eq!(vec![(TokenType::Ident, "x1"),
+                    (TokenType::Ident, "_tmp2"),
+                    (TokenType::Ident, "café"),
+                    (TokenType::Ident, "変数"),
+                    (TokenType::Ident, "δx_0"),
+                    (TokenType::Ident, "a·b"),
+                    (TokenType::Int, "1a"),
+                    (TokenType::EOF, "")],
+               tokens);
+}
+
+#[test]
+fn test_multibyte_span() {
+    let input = "let 変数 = \"ü\";\n  変数 + 🙂";
+
+    let tests = vec![("let", Span::new(0, 3, 1, 1)),
+                     ("変数", Span::new(4, 10, 1, 5)),
+                     ("=", Span::new(11, 12, 1, 8)),
+                     ("\"ü\"", Span::new(13, 17, 1, 10)),
+                     (";", Span::new(17, 18, 1, 13)),
+                     ("変数", Span::new(21, 27, 2, 3)),
+                     ("+", Span::new(28, 29, 2, 6)),
+                     ("🙂", Span::new(30, 34, 2, 8)),
+                     ("", Span::new(34, 34, 2, 9))];
+
+    let mut lexer = Lexer::new(input);
+    for (literal, span) in tests {
+        let token = lexer.next_token();
+        assert_eq!(literal, token.literal);
+        assert_eq!(span, token.span);
+    }
+}
+
+#[test]
+fn test_operators() {
+    let input = "a <= b >= c && d || e % f ** g += h -= i *= j /= k & l | m<=-n !==";
+
+    let tokens = Lexer::new(input)
+        .filter(|t| t.token_type != TokenType::Ident)
+        .map(|t| (t.token_type, t.literal))
+        .collect::<Vec<(TokenType, &str)>>();
+    assert_eq!(vec![(TokenType::LTEQ, "<="),
+                    (TokenType::GTEQ, ">="),
+                    (TokenType::And, "&&"),
+                    (TokenType::Or, "||"),
+                    (TokenType::Percent, "%"),
+                    (TokenType::Power, "**"),
+                    (TokenType::PlusAssign, "+="),
+                    (TokenType::MinusAssign, "-="),
+                    (TokenType::AsteriskAssign, "*="),
+                    (TokenType::SlashAssign, "/="),
+                    (TokenType::Illegal, "&"),
+                    (TokenType::Illegal, "|"),
+                    (TokenType::LTEQ, "<="),
+                    (TokenType::Minus, "-"),
+                    (TokenType::NotEQ, "!="),
+                    (TokenType::Assign, "="),
+                    (TokenType::EOF, "")],
+               tokens);
+}
+
+#[test]
+fn test_input_ending_with_operator() {
+    let tests = vec![("!", vec![TokenType::Bang, TokenType::EOF]),
+                     ("a =", vec![TokenType::Ident, TokenType::Assign, TokenType::EOF]),
+                     ("a !=", vec![TokenType::Ident, TokenType::NotEQ, TokenType::EOF]),
+                     ("a *", vec![TokenType::Ident, TokenType::Asterisk, TokenType::EOF]),
+                     ("a &", vec![TokenType::Ident, TokenType::Illegal, TokenType::EOF])];
+
+    for (input, expected) in tests {
+        let mut lexer = Lexer::new(input);
+        let mut token_types = vec![];
+        loop {
+            let token = lexer.next_token();
+            token_types.push(token.token_type);
+            if token.token_type == TokenType::EOF {
+                break;
+            }
+        }
+        assert_eq!(expected, token_types);
+    }
+}
+
+#[test]
+fn test_iterator() {
+    let token_types = Lexer::new("let x = 5;")
+        .map(|token| token.token_type)
+        .collect::<Vec<TokenType>>();
+
+    assert_eq!(vec![TokenType::Let,
+                    TokenType::Ident,
+                    TokenType::Assign,
+                    TokenType::Int,
+                    TokenType::Semicolon,
+                    TokenType::EOF],
+               token_types);
+    assert_eq!(1, Lexer::new("").count());
+}
+
+#[test]
+fn test_tokenize() {
+    let tokens = tokenize("add(1, 2)").unwrap();
+    let literals = tokens.iter().map(|t| t.literal).collect::<Vec<&str>>();
+    assert_eq!(vec!["add", "(", "1", ",", "2", ")", ""], literals);
+
+    assert_eq!(Err(LexError::new(LexErrorKind::IllegalCharacter,
+                                 "@".to_owned(),
+                                 Span::new(8, 9, 2, 3))),
+               tokenize("let a\n= @ 1 # 2;"));
+    assert_eq!(Err(LexError::new(LexErrorKind::UnterminatedComment,
+                                 "/* a /* b */".to_owned(),
+                                 Span::new(2, 14, 1, 3))),
+               tokenize("1 /* a /* b */"));
+}
+
+#[test]
+fn test_comments() {
+    let input = "let a = 1; // one\n/* two /* nested */ */ a / 2 /**/ * 3 //";
+
+    let token_types = Lexer::new(input).map(|t| t.token_type).collect::<Vec<TokenType>>();
+    assert_eq!(vec![TokenType::Let,
+                    TokenType::Ident,
+                    TokenType::Assign,
+                    TokenType::Int,
+                    TokenType::Semicolon,
+                    TokenType::Ident,
+                    TokenType::Slash,
+                    TokenType::Int,
+                    TokenType::Asterisk,
+                    TokenType::Int,
+                    TokenType::EOF],
+               token_types);
+
+    let comments = Lexer::new(input)
+        .with_comments()
+        .filter(|t| t.token_type.is_trivia())
+        .map(|t| (t.token_type, t.literal, t.span))
+        .collect::<Vec<(TokenType, &str, Span)>>();
+    assert_eq!(vec![(TokenType::LineComment, "// one", Span::new(11, 17, 1, 12)),
+                    (TokenType::BlockComment,
+                     "/* two /* nested */ */",
+                     Span::new(18, 40, 2, 1)),
+                    (TokenType::BlockComment, "/**/", Span::new(47, 51, 2, 30)),
+                    (TokenType::LineComment, "//", Span::new(56, 58, 2, 39))],
+               comments);
+
+    let token = Lexer::new("/* /* */").next_token();
+    assert_eq!(TokenType::UnterminatedComment, token.token_type);
+    assert_eq!(TokenType::EOF, Lexer::new("/* */").next_token().token_type);
+    assert_eq!(TokenType::UnterminatedComment,
+               Lexer::new("/*/").next_token().token_type);
+}
@@ -1,3 +1,5 @@
 mod lexer;
+mod lex_error;
 
-pub use self::lexer::Lexer;
\ No newline at end of file
+pub use self::lexer::{Lexer, tokenize};
+pub use self::lex_error::{LexError, LexErrorKind};
@@ -1,5 +1,13 @@
+#![allow(clippy::module_inception)]
+
+extern crate unicode_xid;
+
 pub mod lexer;
 pub mod token;
 pub mod repl;
 pub mod ast;
-pub mod parser;
\ No newline at end of file
+pub mod parser;
+pub mod evaluator;
+pub mod diagnostics;
+pub mod cli;
+pub mod interpreter;
@@ -1,7 +1,10 @@
 extern crate writing_an_interpreter_in_rust;
 
+use std::env;
+use std::process;
 use writing_an_interpreter_in_rust::*;
 
 fn main() {
-    repl::start();
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    process::exit(cli::run(&args));
 }
@@ -1,5 +1,7 @@
 mod parse_error;
 mod parser;
+mod precedence;
 
 pub use self::parser::*;
 pub use self::parse_error::*;
+pub use self::precedence::*;
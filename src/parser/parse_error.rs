@@ -1,30 +1,81 @@
+use std::error::Error;
 use std::fmt;
-use parser::Parser;
-use token::TokenType;
+use token::{TokenType, Span};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct ParseError {
     kind: ParseErrorKind,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ParseErrorKind {
-    UnexpectedToken(Parser, TokenType),
+    UnexpectedToken {
+        expected: TokenType,
+        found: TokenType,
+        span: Span,
+    },
+    NoPrefixParseFn { token_type: TokenType, span: Span },
+    IntegerOverflow { literal: String, span: Span },
+    MalformedNumber { literal: String, span: Span },
+    UnexpectedEof { expected: TokenType, span: Span },
+    UnterminatedString { span: Span },
+    UnterminatedComment { span: Span },
+    InvalidEscape { sequence: String, span: Span },
+    InvalidAssignmentTarget { span: Span },
+    NestingTooDeep { max: usize, span: Span },
 }
 
 impl ParseError {
     pub fn new(kind: ParseErrorKind) -> Self {
-        ParseError { kind: kind }
+        ParseError { kind }
+    }
+
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+
+    pub fn span(&self) -> Span {
+        match self.kind {
+            ParseErrorKind::UnexpectedToken { span, .. } |
+            ParseErrorKind::NoPrefixParseFn { span, .. } |
+            ParseErrorKind::IntegerOverflow { span, .. } |
+            ParseErrorKind::MalformedNumber { span, .. } |
+            ParseErrorKind::UnexpectedEof { span, .. } |
+            ParseErrorKind::UnterminatedString { span } |
+            ParseErrorKind::UnterminatedComment { span } |
+            ParseErrorKind::InvalidAssignmentTarget { span } |
+            ParseErrorKind::NestingTooDeep { span, .. } |
+            ParseErrorKind::InvalidEscape { span, .. } => span,
+        }
     }
 
     pub fn error_description(&self) -> String {
-        return match self.kind {
-            ParseErrorKind::UnexpectedToken(ref p, ref t) => {
-                format!("expected next token to be {}, got {} instead",
-                        t.clone(),
-                        p.clone().peek_token.token_type)
+        match self.kind {
+            ParseErrorKind::UnexpectedToken { ref expected, ref found, .. } => {
+                format!("expected next token to be {}, got {} instead", expected, found)
+            }
+            ParseErrorKind::NoPrefixParseFn { ref token_type, .. } => {
+                format!("no prefix parse function for {} found", token_type)
+            }
+            ParseErrorKind::IntegerOverflow { ref literal, .. } => {
+                format!("integer literal {} is too large for a 64-bit integer", literal)
             }
-        };
+            ParseErrorKind::MalformedNumber { ref literal, .. } => {
+                format!("malformed number literal {}", literal)
+            }
+            ParseErrorKind::UnexpectedEof { ref expected, .. } => {
+                format!("unexpected end of input, expected {}", expected)
+            }
+            ParseErrorKind::UnterminatedString { .. } => "unterminated string literal".to_owned(),
+            ParseErrorKind::UnterminatedComment { .. } => "unterminated block comment".to_owned(),
+            ParseErrorKind::InvalidEscape { ref sequence, .. } => {
+                format!("invalid escape sequence {}", sequence)
+            }
+            ParseErrorKind::InvalidAssignmentTarget { .. } => {
+                "invalid left-hand side of assignment".to_owned()
+            }
+            ParseErrorKind::NestingTooDeep { .. } => "expression nested too deeply".to_owned(),
+        }
     }
 }
 
@@ -33,3 +84,5 @@ impl fmt::Display for ParseError {
         self.error_description().fmt(f)
     }
 }
+
+impl Error for ParseError {}
@@ -1,114 +1,559 @@
+use std::cmp;
+use std::mem;
+use std::num::IntErrorKind;
 use lexer::Lexer;
-use token::{Token, TokenType};
-use ast::{Program, Statement, LetStatement, Identifier, ReturnStatement};
-use parser::{ParseError, ParseErrorKind};
+use token::{Token, TokenType, Span};
+use ast::{Program, Statement, Expression, LetStatement, Identifier, ReturnStatement,
+          ExpressionStatement, BlockStatement, IntegerLiteral, FloatLiteral, StringLiteral,
+          Boolean, PrefixExpression, InfixExpression, AssignExpression, IfExpression,
+          FunctionLiteral, CallExpression, ArrayLiteral, IndexExpression, HashLiteral};
+use parser::{ParseError, ParseErrorKind, Precedence};
+
+/// How deeply expressions may nest in the source, for example through
+/// brackets, parentheses or prefix operators. The parser recurses once per
+/// level, so this keeps it from overflowing the stack.
+pub const MAX_NESTING_DEPTH: usize = 128;
+
+/// How deep the syntax tree of an expression may be. A chain like `1 + 1 + 1`
+/// nests each operator in the next without the parser recursing, so this is
+/// checked separately. Evaluating and dropping the tree both recurse once per
+/// level.
+pub const MAX_EXPRESSION_DEPTH: usize = 10_000;
+
+type PrefixParseFn<'src> = fn(&mut Parser<'src>) -> Result<Expression, ParseError>;
+type InfixParseFn<'src> = fn(&mut Parser<'src>, Expression) -> Result<Expression, ParseError>;
 
 #[derive(Debug, Clone)]
-pub struct Parser {
-    lexer: Lexer,
-    pub current_token: Token,
-    pub peek_token: Token,
+pub struct Parser<'src> {
+    lexer: Lexer<'src>,
+    pub current_token: Token<'src>,
+    pub peek_token: Token<'src>,
+    errors: Vec<ParseError>,
+    depth: usize,
+    /// The height of the deepest expression parsed since the innermost
+    /// `parse_expression` call started.
+    height: usize,
 }
 
-impl Parser {
-    pub fn new(lexer: Lexer) -> Self {
-        let mut l = lexer.clone();
-        let current_token = l.next_token();
-        let peek_token = l.next_token();
+impl<'src> Parser<'src> {
+    pub fn new(mut lexer: Lexer<'src>) -> Self {
+        let current_token = Parser::next_significant_token(&mut lexer);
+        let peek_token = Parser::next_significant_token(&mut lexer);
 
         Parser {
-            lexer: l,
-            current_token: current_token,
-            peek_token: peek_token,
+            lexer,
+            current_token,
+            peek_token,
+            errors: vec![],
+            depth: 0,
+            height: 0,
         }
     }
 
     fn next_token(&mut self) {
-        self.current_token = self.peek_token.clone();
-        self.peek_token = self.lexer.next_token();
+        self.current_token = self.peek_token;
+        self.peek_token = Parser::next_significant_token(&mut self.lexer);
+    }
+
+    /// Skips comments in case the lexer was built to emit them.
+    fn next_significant_token(lexer: &mut Lexer<'src>) -> Token<'src> {
+        loop {
+            let token = lexer.next_token();
+            if !token.token_type.is_trivia() {
+                return token;
+            }
+        }
+    }
+
+    pub fn parser_program(&mut self) -> Result<Program, Vec<ParseError>> {
+        let (program, errors) = self.parse_program_with_errors();
+
+        if errors.is_empty() {
+            Ok(program)
+        } else {
+            Err(errors)
+        }
     }
 
-    pub fn parser_program(&mut self) -> Result<Program, ParseError> {
-        let statements: Vec<Box<Statement>> = vec![];
-        let mut program = Program::new(statements);
+    /// Parses the whole input, recovering from syntax errors at statement
+    /// boundaries. Returns every statement that could be parsed together with
+    /// every error that was encountered, in source order.
+    pub fn parse_program_with_errors(&mut self) -> (Program, Vec<ParseError>) {
+        let mut program = Program::new(vec![]);
 
         loop {
             if self.current_token_is(TokenType::EOF) {
                 break;
             }
 
-            let s = self.parse_statement()?;
-            program.statements.push(s);
+            match self.parse_statement() {
+                Ok(s) => {
+                    program.statements.push(s);
+                    self.next_token();
+                }
+                Err(err) => {
+                    self.errors.push(err);
+                    self.synchronize();
 
-            self.next_token();
+                    // A stray closing brace can't start a statement at the top level.
+                    if self.current_token_is(TokenType::RBrace) {
+                        self.next_token();
+                    }
+                }
+            }
         }
 
-        Ok(program)
+        (program, mem::take(&mut self.errors))
     }
 
-    fn parse_statement(&mut self) -> Result<Box<Statement>, ParseError> {
-        return match self.current_token.token_type {
-            TokenType::Let => {
-                let let_statement = self.parse_let_statement()?;
-                let s: Result<Box<Statement>, _> = Ok(Box::new(let_statement));
-                return s;
-            }
-            TokenType::Return => {
-                let return_statement = self.parse_return_statement()?;
-                let s: Result<Box<Statement>, _> = Ok(Box::new(return_statement));
-                return s;
+    /// Skips the remainder of a statement that failed to parse. Stops after a
+    /// `;`, on a `}` or on a statement keyword, so that `current_token` is
+    /// where the next statement (or the end of the enclosing block) begins.
+    fn synchronize(&mut self) {
+        loop {
+            match self.current_token.token_type {
+                TokenType::EOF | TokenType::RBrace => return,
+                TokenType::Semicolon => {
+                    self.next_token();
+                    return;
+                }
+                _ => {}
             }
-            _ => {
-                Err(ParseError::new(ParseErrorKind::UnexpectedToken(self.clone(),
-                                                                    self.current_token
-                                                                        .token_type
-                                                                        .clone())))
+
+            self.next_token();
+
+            if self.current_token_is(TokenType::Let) || self.current_token_is(TokenType::Return) {
+                return;
             }
-        };
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement, ParseError> {
+        match self.current_token.token_type {
+            TokenType::Let => Ok(Statement::Let(self.parse_let_statement()?)),
+            TokenType::Return => Ok(Statement::Return(self.parse_return_statement()?)),
+            _ => Ok(Statement::Expression(self.parse_expression_statement()?)),
+        }
     }
 
     fn parse_let_statement(&mut self) -> Result<LetStatement, ParseError> {
-        let token = self.current_token.clone();
+        let start = self.current_token.span;
 
         self.expect_peek(TokenType::Ident)?;
 
-        let name = Identifier::new(self.current_token.clone(),
-                                   self.current_token.literal.clone());
+        let name = Identifier::new(self.current_token.literal.to_owned(), self.current_token.span);
+
+        self.expect_peek(TokenType::Assign)?;
+        self.next_token();
+
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+        }
+
+        Ok(LetStatement::new(name, value, start.to(self.current_token.span)))
+    }
+
+    fn parse_return_statement(&mut self) -> Result<ReturnStatement, ParseError> {
+        let start = self.current_token.span;
+
+        self.next_token();
+
+        let return_value = self.parse_expression(Precedence::Lowest)?;
+
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+        }
+
+        Ok(ReturnStatement::new(return_value, start.to(self.current_token.span)))
+    }
+
+    fn parse_expression_statement(&mut self) -> Result<ExpressionStatement, ParseError> {
+        let start = self.current_token.span;
+        let expression = self.parse_expression(Precedence::Lowest)?;
 
-        let statement = LetStatement {
-            token: token,
-            name: name,
-            value: None,
+        if self.peek_token_is(TokenType::Semicolon) {
+            self.next_token();
+        }
+
+        Ok(ExpressionStatement::new(expression, start.to(self.current_token.span)))
+    }
+
+    fn parse_expression(&mut self, precedence: Precedence) -> Result<Expression, ParseError> {
+        if self.depth == MAX_NESTING_DEPTH {
+            return Err(self.nesting_too_deep(MAX_NESTING_DEPTH));
+        }
+
+        self.depth += 1;
+        let outer = mem::replace(&mut self.height, 0);
+        let result = self.parse_expression_unchecked(precedence);
+        self.depth -= 1;
+        self.height = cmp::max(outer, self.height);
+        result
+    }
+
+    fn nesting_too_deep(&self, max: usize) -> ParseError {
+        ParseError::new(ParseErrorKind::NestingTooDeep {
+            max,
+            span: self.current_token.span,
+        })
+    }
+
+    fn parse_expression_unchecked(&mut self,
+                                  precedence: Precedence)
+                                  -> Result<Expression, ParseError> {
+        let prefix = match Parser::prefix_parse_fn(&self.current_token.token_type) {
+            Some(prefix) => prefix,
+            None if self.current_token_is(TokenType::UnterminatedComment) => {
+                return Err(unterminated_comment(self.current_token.span));
+            }
+            None => {
+                let kind = ParseErrorKind::NoPrefixParseFn {
+                    token_type: self.current_token.token_type,
+                    span: self.current_token.span,
+                };
+                return Err(ParseError::new(kind));
+            }
         };
 
-        self.expect_peek(TokenType::Assign)?;
+        let mut left = prefix(self)?;
+        let mut height = self.height + 1;
 
         loop {
-            if self.current_token_is(TokenType::Semicolon) {
+            if height > MAX_EXPRESSION_DEPTH {
+                return Err(self.nesting_too_deep(MAX_EXPRESSION_DEPTH));
+            }
+            if self.peek_token_is(TokenType::Semicolon) || precedence >= self.peek_precedence() {
                 break;
             }
+            let infix = match Parser::infix_parse_fn(&self.peek_token.token_type) {
+                Some(infix) => infix,
+                None => break,
+            };
+
             self.next_token();
+            self.height = 0;
+            left = infix(self, left)?;
+            // `left` is now an operand of the new node.
+            height = cmp::max(height, self.height) + 1;
         }
 
-        Ok(statement)
+        self.height = height;
+        Ok(left)
     }
 
-    fn parse_return_statement(&mut self) -> Result<ReturnStatement, ParseError> {
-        let statement = ReturnStatement {
-            token: self.current_token.clone(),
-            return_value: None,
+    fn prefix_parse_fn(token_type: &TokenType) -> Option<PrefixParseFn<'src>> {
+        match *token_type {
+            TokenType::Ident => Some(Parser::parse_identifier),
+            TokenType::Int => Some(Parser::parse_integer_literal),
+            TokenType::Float => Some(Parser::parse_float_literal),
+            TokenType::String => Some(Parser::parse_string_literal),
+            TokenType::True | TokenType::False => Some(Parser::parse_boolean),
+            TokenType::Bang | TokenType::Minus => Some(Parser::parse_prefix_expression),
+            TokenType::LParen => Some(Parser::parse_grouped_expression),
+            TokenType::If => Some(Parser::parse_if_expression),
+            TokenType::Function => Some(Parser::parse_function_literal),
+            TokenType::LBracket => Some(Parser::parse_array_literal),
+            // Blocks are only parsed where a construct expects one, so a `{` that
+            // starts an expression is always a hash literal.
+            TokenType::LBrace => Some(Parser::parse_hash_literal),
+            _ => None,
+        }
+    }
+
+    fn infix_parse_fn(token_type: &TokenType) -> Option<InfixParseFn<'src>> {
+        match *token_type {
+            TokenType::Plus | TokenType::Minus | TokenType::Slash | TokenType::Asterisk |
+            TokenType::Percent | TokenType::Power | TokenType::EQ | TokenType::NotEQ |
+            TokenType::LT | TokenType::GT | TokenType::LTEQ | TokenType::GTEQ |
+            TokenType::And | TokenType::Or => Some(Parser::parse_infix_expression),
+            TokenType::PlusAssign | TokenType::MinusAssign | TokenType::AsteriskAssign |
+            TokenType::SlashAssign => Some(Parser::parse_assign_expression),
+            TokenType::LParen => Some(Parser::parse_call_expression),
+            TokenType::LBracket => Some(Parser::parse_index_expression),
+            _ => None,
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<Expression, ParseError> {
+        Ok(Expression::Identifier(Identifier::new(self.current_token.literal.to_owned(),
+                                                  self.current_token.span)))
+    }
+
+    fn parse_integer_literal(&mut self) -> Result<Expression, ParseError> {
+        let literal = self.current_token.literal;
+        let (digits, radix) = match literal.get(..2) {
+            Some("0x") | Some("0X") => (&literal[2..], 16),
+            Some("0o") | Some("0O") => (&literal[2..], 8),
+            Some("0b") | Some("0B") => (&literal[2..], 2),
+            _ => (literal, 10),
         };
 
+        match i64::from_str_radix(&digits.replace('_', ""), radix) {
+            Ok(value) => {
+                Ok(Expression::IntegerLiteral(IntegerLiteral::new(value, self.current_token.span)))
+            }
+            Err(ref err) if *err.kind() == IntErrorKind::PosOverflow => {
+                let kind = ParseErrorKind::IntegerOverflow {
+                    literal: literal.to_owned(),
+                    span: self.current_token.span,
+                };
+                Err(ParseError::new(kind))
+            }
+            Err(_) => Err(self.malformed_number()),
+        }
+    }
+
+    fn parse_float_literal(&mut self) -> Result<Expression, ParseError> {
+        match self.current_token.literal.replace('_', "").parse::<f64>() {
+            Ok(value) => {
+                Ok(Expression::FloatLiteral(FloatLiteral::new(value, self.current_token.span)))
+            }
+            Err(_) => Err(self.malformed_number()),
+        }
+    }
+
+    fn malformed_number(&self) -> ParseError {
+        ParseError::new(ParseErrorKind::MalformedNumber {
+            literal: self.current_token.literal.to_owned(),
+            span: self.current_token.span,
+        })
+    }
+
+    fn parse_string_literal(&mut self) -> Result<Expression, ParseError> {
+        let value = unescape(self.current_token.literal, self.current_token.span)?;
+        Ok(Expression::StringLiteral(StringLiteral::new(value, self.current_token.span)))
+    }
+
+    fn parse_boolean(&mut self) -> Result<Expression, ParseError> {
+        let value = self.current_token_is(TokenType::True);
+        Ok(Expression::Boolean(Boolean::new(value, self.current_token.span)))
+    }
+
+    fn parse_prefix_expression(&mut self) -> Result<Expression, ParseError> {
+        let start = self.current_token.span;
+        let operator = self.current_token.literal.to_owned();
+
         self.next_token();
 
-        loop {
-            if self.current_token_is(TokenType::Semicolon) {
-                break;
+        let right = self.parse_expression(Precedence::Prefix)?;
+
+        let span = start.to(right.span());
+        Ok(Expression::Prefix(PrefixExpression::new(operator, right, span)))
+    }
+
+    fn parse_infix_expression(&mut self, left: Expression) -> Result<Expression, ParseError> {
+        let operator = self.current_token.literal.to_owned();
+        let precedence = match self.current_precedence() {
+            // Parsing the right operand one level lower makes `**` right-associative.
+            Precedence::Power => Precedence::Prefix,
+            precedence => precedence,
+        };
+
+        self.next_token();
+
+        let right = self.parse_expression(precedence)?;
+
+        let span = left.span().to(right.span());
+        Ok(Expression::Infix(InfixExpression::new(left, operator, right, span)))
+    }
+
+    fn parse_assign_expression(&mut self, left: Expression) -> Result<Expression, ParseError> {
+        let name = match left {
+            Expression::Identifier(name) => name,
+            other => {
+                let kind = ParseErrorKind::InvalidAssignmentTarget { span: other.span() };
+                return Err(ParseError::new(kind));
             }
+        };
+        let operator = self.current_token.literal.to_owned();
+
+        self.next_token();
+
+        // Assignments are right-associative: `a += b += 1` is `a += (b += 1)`.
+        let value = self.parse_expression(Precedence::Lowest)?;
+
+        let span = name.span.to(value.span());
+        Ok(Expression::Assign(AssignExpression::new(name, operator, value, span)))
+    }
+
+    fn parse_grouped_expression(&mut self) -> Result<Expression, ParseError> {
+        self.next_token();
+
+        let expression = self.parse_expression(Precedence::Lowest)?;
+
+        self.expect_peek(TokenType::RParen)?;
+
+        Ok(expression)
+    }
+
+    fn parse_if_expression(&mut self) -> Result<Expression, ParseError> {
+        let start = self.current_token.span;
+
+        self.expect_peek(TokenType::LParen)?;
+        self.next_token();
+
+        let condition = self.parse_expression(Precedence::Lowest)?;
+
+        self.expect_peek(TokenType::RParen)?;
+        self.expect_peek(TokenType::LBrace)?;
+
+        let consequence = self.parse_block_statement()?;
+
+        let alternative = if self.peek_token_is(TokenType::Else) {
             self.next_token();
+            self.expect_peek(TokenType::LBrace)?;
+            Some(self.parse_block_statement()?)
+        } else {
+            None
+        };
+
+        let span = start.to(self.current_token.span);
+        Ok(Expression::If(IfExpression::new(condition, consequence, alternative, span)))
+    }
+
+    fn parse_block_statement(&mut self) -> Result<BlockStatement, ParseError> {
+        let start = self.current_token.span;
+        let mut statements = vec![];
+
+        self.next_token();
+
+        while !self.current_token_is(TokenType::RBrace) {
+            if self.current_token_is(TokenType::EOF) {
+                let kind = ParseErrorKind::UnexpectedEof {
+                    expected: TokenType::RBrace,
+                    span: self.current_token.span,
+                };
+                return Err(ParseError::new(kind));
+            }
+
+            match self.parse_statement() {
+                Ok(s) => {
+                    statements.push(s);
+                    self.next_token();
+                }
+                Err(err) => {
+                    self.errors.push(err);
+                    self.synchronize();
+                }
+            }
         }
 
-        Ok(statement)
+        Ok(BlockStatement::new(statements, start.to(self.current_token.span)))
+    }
+
+    fn parse_function_literal(&mut self) -> Result<Expression, ParseError> {
+        let start = self.current_token.span;
+
+        self.expect_peek(TokenType::LParen)?;
+
+        let parameters = self.parse_function_parameters()?;
+
+        self.expect_peek(TokenType::LBrace)?;
+
+        let body = self.parse_block_statement()?;
+
+        let span = start.to(self.current_token.span);
+        Ok(Expression::FunctionLiteral(FunctionLiteral::new(parameters, body, span)))
+    }
+
+    fn parse_function_parameters(&mut self) -> Result<Vec<Identifier>, ParseError> {
+        let mut identifiers = vec![];
+
+        if self.peek_token_is(TokenType::RParen) {
+            self.next_token();
+            return Ok(identifiers);
+        }
+
+        self.expect_peek(TokenType::Ident)?;
+        identifiers.push(Identifier::new(self.current_token.literal.to_owned(),
+                                         self.current_token.span));
+
+        while self.peek_token_is(TokenType::Comma) {
+            self.next_token();
+            self.expect_peek(TokenType::Ident)?;
+            identifiers.push(Identifier::new(self.current_token.literal.to_owned(),
+                                             self.current_token.span));
+        }
+
+        self.expect_peek(TokenType::RParen)?;
+
+        Ok(identifiers)
+    }
+
+    fn parse_call_expression(&mut self, function: Expression) -> Result<Expression, ParseError> {
+        let arguments = self.parse_expression_list(TokenType::RParen)?;
+
+        let span = function.span().to(self.current_token.span);
+        Ok(Expression::Call(CallExpression::new(function, arguments, span)))
+    }
+
+    fn parse_array_literal(&mut self) -> Result<Expression, ParseError> {
+        let start = self.current_token.span;
+        let elements = self.parse_expression_list(TokenType::RBracket)?;
+
+        let span = start.to(self.current_token.span);
+        Ok(Expression::Array(ArrayLiteral::new(elements, span)))
+    }
+
+    fn parse_hash_literal(&mut self) -> Result<Expression, ParseError> {
+        let start = self.current_token.span;
+        let mut pairs = vec![];
+
+        while !self.peek_token_is(TokenType::RBrace) {
+            self.next_token();
+            let key = self.parse_expression(Precedence::Lowest)?;
+
+            self.expect_peek(TokenType::Colon)?;
+            self.next_token();
+            let value = self.parse_expression(Precedence::Lowest)?;
+
+            pairs.push((key, value));
+
+            if !self.peek_token_is(TokenType::RBrace) {
+                self.expect_peek(TokenType::Comma)?;
+            }
+        }
+
+        self.expect_peek(TokenType::RBrace)?;
+
+        let span = start.to(self.current_token.span);
+        Ok(Expression::Hash(HashLiteral::new(pairs, span)))
+    }
+
+    fn parse_index_expression(&mut self, left: Expression) -> Result<Expression, ParseError> {
+        self.next_token();
+
+        let index = self.parse_expression(Precedence::Lowest)?;
+
+        self.expect_peek(TokenType::RBracket)?;
+
+        let span = left.span().to(self.current_token.span);
+        Ok(Expression::Index(IndexExpression::new(left, index, span)))
+    }
+
+    /// Parses comma-separated expressions up to and including `end`.
+    fn parse_expression_list(&mut self, end: TokenType) -> Result<Vec<Expression>, ParseError> {
+        let mut list = vec![];
+
+        if self.peek_token_is(end) {
+            self.next_token();
+            return Ok(list);
+        }
+
+        self.next_token();
+        list.push(self.parse_expression(Precedence::Lowest)?);
+
+        while self.peek_token_is(TokenType::Comma) {
+            self.next_token();
+            self.next_token();
+            list.push(self.parse_expression(Precedence::Lowest)?);
+        }
+
+        self.expect_peek(end)?;
+
+        Ok(list)
     }
 
     fn current_token_is(&self, token_type: TokenType) -> bool {
@@ -119,36 +564,207 @@ impl Parser {
         self.peek_token.token_type == token_type
     }
 
+    fn current_precedence(&self) -> Precedence {
+        Precedence::from_token_type(&self.current_token.token_type)
+    }
+
+    fn peek_precedence(&self) -> Precedence {
+        Precedence::from_token_type(&self.peek_token.token_type)
+    }
+
     fn expect_peek(&mut self, token_type: TokenType) -> Result<(), ParseError> {
-        if self.peek_token_is(token_type.clone()) {
+        if self.peek_token_is(token_type) {
             self.next_token();
             return Ok(());
         }
-        Err(ParseError::new(ParseErrorKind::UnexpectedToken(self.clone(), token_type.clone())))
+
+        let span = self.peek_token.span;
+        let kind = match self.peek_token.token_type {
+            TokenType::EOF => {
+                ParseErrorKind::UnexpectedEof {
+                    expected: token_type,
+                    span,
+                }
+            }
+            TokenType::UnterminatedComment => return Err(unterminated_comment(span)),
+            found => {
+                ParseErrorKind::UnexpectedToken {
+                    expected: token_type,
+                    found,
+                    span,
+                }
+            }
+        };
+        Err(ParseError::new(kind))
+    }
+}
+
+/// An unterminated block comment runs to the end of the input, so the error
+/// points at its opening `/*` only.
+fn unterminated_comment(span: Span) -> ParseError {
+    let span = Span::new(span.start, span.start + 2, span.line, span.column);
+    ParseError::new(ParseErrorKind::UnterminatedComment { span })
+}
+
+/// Decodes the contents of a string token, whose literal still carries the
+/// quotes and escape sequences exactly as written.
+fn unescape(literal: &str, span: Span) -> Result<String, ParseError> {
+    let mut value = String::with_capacity(literal.len());
+    let (mut line, mut column) = (span.line, span.column + 1);
+    let mut rest = &literal[1..];
+
+    while let Some(ch) = rest.chars().next() {
+        let (decoded, len) = match ch {
+            '"' => return Ok(value),
+            '\\' if rest.len() == 1 => break,
+            '\\' => {
+                decode_escape(rest).map_err(|len| {
+                    let start = span.start + literal.len() - rest.len();
+                    let kind = ParseErrorKind::InvalidEscape {
+                        sequence: rest[..len].to_owned(),
+                        span: Span::new(start, start + len, line, column),
+                    };
+                    ParseError::new(kind)
+                })?
+            }
+            _ => (ch, ch.len_utf8()),
+        };
+
+        value.push(decoded);
+        for ch in rest[..len].chars() {
+            if ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        rest = &rest[len..];
+    }
+
+    let span = Span::new(span.start, span.start + 1, span.line, span.column);
+    Err(ParseError::new(ParseErrorKind::UnterminatedString { span }))
+}
+
+/// Decodes the escape sequence `s` starts with into the character it stands
+/// for and its length in bytes. On failure, returns the length of the invalid
+/// sequence instead.
+fn decode_escape(s: &str) -> Result<(char, usize), usize> {
+    match s[1..].chars().next() {
+        Some('n') => Ok(('\n', 2)),
+        Some('t') => Ok(('\t', 2)),
+        Some('"') => Ok(('"', 2)),
+        Some('\\') => Ok(('\\', 2)),
+        Some('u') if s[2..].starts_with('{') => {
+            let hex = &s[3..];
+            let digits = hex.len() - hex.trim_start_matches(|c: char| c.is_ascii_hexdigit()).len();
+            if !s[3 + digits..].starts_with('}') {
+                return Err(3 + digits);
+            }
+
+            let len = 3 + digits + 1;
+            if digits == 0 || digits > 6 {
+                return Err(len);
+            }
+            u32::from_str_radix(&hex[..digits], 16)
+                .ok()
+                .and_then(char::from_u32)
+                .map(|ch| (ch, len))
+                .ok_or(len)
+        }
+        Some(ch) => Err(1 + ch.len_utf8()),
+        None => Err(1),
     }
 }
 
+#[cfg(test)]
+fn parse(input: &str) -> Program {
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    match parser.parser_program() {
+        Ok(p) => p,
+        Err(errors) => panic!("{}", errors[0].error_description()),
+    }
+}
+
+#[cfg(test)]
+fn span(start: usize, end: usize) -> Span {
+    Span::new(start, end, 1, start + 1)
+}
+
+#[cfg(test)]
+fn identifier(value: &str, start: usize) -> Expression {
+    Expression::Identifier(Identifier::new(value.to_owned(), span(start, start + value.len())))
+}
+
+#[cfg(test)]
+fn integer(value: i64, start: usize) -> Expression {
+    let end = start + value.to_string().len();
+    Expression::IntegerLiteral(IntegerLiteral::new(value, span(start, end)))
+}
+
+#[cfg(test)]
+fn expression_statement(expression: Expression, end: usize) -> Statement {
+    let span = expression.span();
+    Statement::Expression(ExpressionStatement::new(expression, Span { end, ..span }))
+}
+
 #[test]
 fn test_let_statement() {
     let input = r#"
     let x = 5;
     let y = 10;
     let foobar = 838383;
-    "#
-        .to_owned();
+    "#;
 
     let lexer = Lexer::new(input);
     let mut parser = Parser::new(lexer);
 
     match parser.parser_program() {
         Ok(p) => {
-            assert_eq!(3, p.statements.len());
-            for s in p.statements {
-                assert_eq!("let".to_owned(), s.token_literal());
+            let names = vec![("x", 2), ("y", 3), ("foobar", 4)];
+            assert_eq!(names.len(), p.statements.len());
+            for (s, (name, line)) in p.statements.into_iter().zip(names) {
+                match s {
+                    Statement::Let(s) => {
+                        assert_eq!(name, s.name.value);
+                        assert_eq!(line, s.span.line);
+                        assert_eq!(5, s.span.column);
+                    }
+                    other => panic!("statement is not Let. got={:?}", other),
+                }
             }
         }
-        Err(err) => panic!("{}", err.error_description()),
+        Err(errors) => panic!("{}", errors[0].error_description()),
     };
+
+    let expected = vec![("x", 4, integer(5, 8), span(0, 10)),
+                        ("y", 15, integer(10, 19), span(11, 22)),
+                        ("foobar", 27, integer(838383, 36), span(23, 43))]
+        .into_iter()
+        .map(|(name, start, value, span_)| {
+            let name = Identifier::new(name.to_owned(), span(start, start + name.len()));
+            Statement::Let(LetStatement::new(name, value, span_))
+        })
+        .collect::<Vec<Statement>>();
+
+    assert_eq!(expected,
+               parse("let x = 5; let y = 10; let foobar = 838383;").statements);
+
+    let name = Identifier::new("foobar".to_owned(), span(4, 10));
+    let expected = Statement::Let(LetStatement::new(name, identifier("y", 13), span(0, 15)));
+    assert_eq!(vec![expected], parse("let foobar = y;").statements);
+
+    let tests = vec![("let x = 5;", "let x = 5;"),
+                     ("let y = true;", "let y = true;"),
+                     ("let foobar = y;", "let foobar = y;")];
+
+    for (input, expected) in tests {
+        let program = parse(input);
+        assert_eq!(1, program.statements.len());
+        assert_eq!(expected, program.to_string());
+    }
 }
 
 #[test]
@@ -157,16 +773,15 @@ fn test_invalid_let_statement() {
     let x = 5;
     let = 10;
     let foobar = 838383;
-    "#
-        .to_owned();
+    "#;
 
     let lexer = Lexer::new(input);
     let mut parser = Parser::new(lexer);
 
-    let program = parser.parser_program();
-    assert!(program.is_err());
+    let errors = parser.parser_program().err().unwrap();
+    assert_eq!(1, errors.len());
     assert_eq!("expected next token to be Ident, got Assign instead",
-               program.err().unwrap().error_description());
+               errors[0].error_description());
 }
 
 #[test]
@@ -175,8 +790,7 @@ fn test_return_statement() {
     return 5;
     return 10;
     return add(15);
-    "#
-        .to_owned();
+    "#;
 
     let lexer = Lexer::new(input);
     let mut parser = Parser::new(lexer);
@@ -184,6 +798,547 @@ fn test_return_statement() {
     let program = parser.parser_program().unwrap();
     assert_eq!(3, program.statements.len());
     for s in program.statements {
-        assert_eq!("return".to_owned(), s.token_literal());
+        match s {
+            Statement::Return(_) => {}
+            other => panic!("statement is not Return. got={:?}", other),
+        }
+    }
+
+    assert_eq!("return (x + 1);", parse("return x + 1;").to_string());
+}
+
+#[test]
+fn test_identifier_and_integer_literal_expression() {
+    let program = parse("foobar; 5;");
+
+    let expected = vec![expression_statement(identifier("foobar", 0), 7),
+                        expression_statement(integer(5, 8), 10)];
+
+    assert_eq!(expected, program.statements);
+}
+
+#[test]
+fn test_number_literal_expression() {
+    let tests = vec![("42", 42), ("1_000_000", 1000000), ("0xFF", 255), ("0o755", 493),
+                     ("0b1010", 10), ("0x_dead_beef", 0xdead_beef),
+                     ("9223372036854775807", i64::MAX), ("0x7fff_ffff_ffff_ffff", i64::MAX)];
+
+    for (input, value) in tests {
+        let expected = Expression::IntegerLiteral(IntegerLiteral::new(value, span(0, input.len())));
+        assert_eq!(vec![expression_statement(expected, input.len())], parse(input).statements);
+    }
+
+    let tests = vec![("2.75", 2.75), ("1e-9", 1e-9), ("2.5E+3", 2500.0), ("1_000.5", 1000.5)];
+
+    for (input, value) in tests {
+        let expected = Expression::FloatLiteral(FloatLiteral::new(value, span(0, input.len())));
+        assert_eq!(vec![expression_statement(expected, input.len())], parse(input).statements);
+    }
+
+    assert_eq!("((-1.5) + (3.0 * 0.001))", parse("-1.5 + 3.0 * 1e-3").to_string());
+}
+
+#[test]
+fn test_string_literal_expression() {
+    let expected = Expression::StringLiteral(StringLiteral::new("hello world".to_owned(),
+                                                                span(0, 13)));
+    assert_eq!(vec![expression_statement(expected, 14)],
+               parse(r#""hello world";"#).statements);
+
+    let tests = vec![(r#""""#, ""),
+                     (r#""a\nb""#, "a\nb"),
+                     (r#""\ttab""#, "\ttab"),
+                     (r#""say \"hi\"""#, "say \"hi\""),
+                     (r#""back\\slash""#, "back\\slash"),
+                     (r#""\u{48}\u{e9}\u{1F600}""#, "H\u{e9}\u{1F600}"),
+                     ("\"multi\nline\"", "multi\nline")];
+
+    for (input, expected) in tests {
+        match parse(input).statements[0] {
+            Statement::Expression(ExpressionStatement {
+                expression: Expression::StringLiteral(ref s), ..
+            }) => assert_eq!(expected, s.value),
+            ref other => panic!("statement is not a string literal. got={:?}", other),
+        }
+    }
+
+    assert_eq!(r#""a\"b\n""#, parse(r#""a\"b\n""#).to_string());
+}
+
+#[test]
+fn test_prefix_expression() {
+    let tests = vec![("!5;", "(!5)"),
+                     ("-15;", "(-15)"),
+                     ("!-a", "(!(-a))"),
+                     ("!true", "(!true)"),
+                     ("!false", "(!false)")];
+
+    for (input, expected) in tests {
+        let program = parse(input);
+        assert_eq!(1, program.statements.len());
+        assert_eq!(expected, program.to_string());
+    }
+
+    let expected = Expression::Prefix(PrefixExpression::new("-".to_owned(),
+                                                            integer(15, 1),
+                                                            span(0, 3)));
+    assert_eq!(vec![expression_statement(expected, 4)], parse("-15;").statements);
+}
+
+#[test]
+fn test_infix_expression() {
+    let tests = vec![("5 + 5;", "(5 + 5)"),
+                     ("5 - 5;", "(5 - 5)"),
+                     ("5 * 5;", "(5 * 5)"),
+                     ("5 / 5;", "(5 / 5)"),
+                     ("5 > 5;", "(5 > 5)"),
+                     ("5 < 5;", "(5 < 5)"),
+                     ("5 == 5;", "(5 == 5)"),
+                     ("5 != 5;", "(5 != 5)"),
+                     ("5 % 5;", "(5 % 5)"),
+                     ("5 ** 5;", "(5 ** 5)"),
+                     ("5 <= 5;", "(5 <= 5)"),
+                     ("5 >= 5;", "(5 >= 5)"),
+                     ("a && b;", "(a && b)"),
+                     ("a || b;", "(a || b)"),
+                     ("x += 1;", "(x += 1)"),
+                     ("x -= 1;", "(x -= 1)"),
+                     ("x *= 1;", "(x *= 1)"),
+                     ("x /= 1;", "(x /= 1)"),
+                     ("true == true", "(true == true)"),
+                     ("true != false", "(true != false)")];
+
+    for (input, expected) in tests {
+        let program = parse(input);
+        assert_eq!(1, program.statements.len());
+        assert_eq!(expected, program.to_string());
+    }
+
+    let expected = Expression::Infix(InfixExpression::new(identifier("alice", 0),
+                                                          "*".to_owned(),
+                                                          identifier("bob", 8),
+                                                          span(0, 11)));
+    assert_eq!(vec![expression_statement(expected, 12)],
+               parse("alice * bob;").statements);
+}
+
+#[test]
+fn test_operator_precedence_parsing() {
+    let tests = vec![("-a * b", "((-a) * b)"),
+                     ("!-a", "(!(-a))"),
+                     ("a + b + c", "((a + b) + c)"),
+                     ("a + b - c", "((a + b) - c)"),
+                     ("a * b * c", "((a * b) * c)"),
+                     ("a * b / c", "((a * b) / c)"),
+                     ("a + b / c", "(a + (b / c))"),
+                     ("a + b * c + d / e - f", "(((a + (b * c)) + (d / e)) - f)"),
+                     ("3 + 4; -5 * 5", "(3 + 4)((-5) * 5)"),
+                     ("5 > 4 == 3 < 4", "((5 > 4) == (3 < 4))"),
+                     ("5 < 4 != 3 > 4", "((5 < 4) != (3 > 4))"),
+                     ("3 + 4 * 5 == 3 * 1 + 4 * 5", "((3 + (4 * 5)) == ((3 * 1) + (4 * 5)))"),
+                     ("1 + (2 + 3) + 4", "((1 + (2 + 3)) + 4)"),
+                     ("(5 + 5) * 2", "((5 + 5) * 2)"),
+                     ("2 / (5 + 5)", "(2 / (5 + 5))"),
+                     ("-(5 + 5)", "(-(5 + 5))"),
+                     ("!(a == b)", "(!(a == b))"),
+                     ("3 > 5 == false", "((3 > 5) == false)"),
+                     ("!(true == true)", "(!(true == true))"),
+                     ("a + add(b * c) + d", "((a + add((b * c))) + d)"),
+                     ("add(a, b, 1, 2 * 3, 4 + 5, add(6, 7 * 8))",
+                      "add(a, b, 1, (2 * 3), (4 + 5), add(6, (7 * 8)))"),
+                     ("add(a + b + c * d / f + g)", "add((((a + b) + ((c * d) / f)) + g))"),
+                     ("a + b % c", "(a + (b % c))"),
+                     ("a * b % c", "((a * b) % c)"),
+                     ("2 ** 3 ** 2", "(2 ** (3 ** 2))"),
+                     ("-2 ** 2", "(-(2 ** 2))"),
+                     ("2 ** -1", "(2 ** (-1))"),
+                     ("a * b ** c", "(a * (b ** c))"),
+                     ("f(x) ** 2", "(f(x) ** 2)"),
+                     ("a <= b == c >= d", "((a <= b) == (c >= d))"),
+                     ("a || b && c", "(a || (b && c))"),
+                     ("a && b || c && d", "((a && b) || (c && d))"),
+                     ("a == b && c != d", "((a == b) && (c != d))"),
+                     ("!a && b", "((!a) && b)"),
+                     ("x += y || z", "(x += (y || z))"),
+                     ("x += y *= 2", "(x += (y *= 2))"),
+                     ("let x = y -= 1 + 2;", "let x = (y -= (1 + 2));")];
+
+    for (input, expected) in tests {
+        assert_eq!(expected, parse(input).to_string());
+    }
+}
+
+#[test]
+fn test_if_expression() {
+    let tests = vec![("if (x < y) { x }", "if (x < y) { x }"),
+                     ("if (x < y) { x } else { y }", "if (x < y) { x } else { y }"),
+                     ("if (x) { let z = x; z }", "if x { let z = x;z }")];
+
+    for (input, expected) in tests {
+        let program = parse(input);
+        assert_eq!(1, program.statements.len());
+        assert_eq!(expected, program.to_string());
+    }
+
+    let condition = Expression::Infix(InfixExpression::new(identifier("x", 4),
+                                                           "<".to_owned(),
+                                                           identifier("y", 8),
+                                                           span(4, 9)));
+    let consequence = BlockStatement::new(vec![expression_statement(identifier("x", 13), 14)],
+                                          span(11, 16));
+    let expected = Expression::If(IfExpression::new(condition, consequence, None, span(0, 16)));
+    assert_eq!(vec![expression_statement(expected, 16)],
+               parse("if (x < y) { x }").statements);
+}
+
+#[test]
+fn test_function_literal() {
+    let tests = vec![("fn() {};", "fn() {  }"),
+                     ("fn(x) {};", "fn(x) {  }"),
+                     ("fn(x, y, z) { x + y; }", "fn(x, y, z) { (x + y) }")];
+
+    for (input, expected) in tests {
+        let program = parse(input);
+        assert_eq!(1, program.statements.len());
+        assert_eq!(expected, program.to_string());
+    }
+}
+
+#[test]
+fn test_call_expression() {
+    let program = parse("let add = fn(x, y) { x + y; }; add(five, ten);");
+
+    assert_eq!(2, program.statements.len());
+    assert_eq!("let add = fn(x, y) { (x + y) };", program.statements[0].to_string());
+    assert_eq!("add(five, ten)", program.statements[1].to_string());
+
+    let expected = CallExpression::new(identifier("add", 31),
+                                       vec![identifier("five", 35), identifier("ten", 41)],
+                                       span(31, 45));
+    assert_eq!(expression_statement(Expression::Call(expected), 46),
+               program.statements[1]);
+
+    match program.statements[0] {
+        Statement::Let(ref s) => assert_eq!(span(10, 29), s.value.span()),
+        ref other => panic!("statement is not Let. got={:?}", other),
+    }
+}
+
+#[test]
+fn test_array_literal() {
+    let program = parse("[1, 2 * 2, 3 + 3]");
+
+    let elements = vec![integer(1, 1),
+                        Expression::Infix(InfixExpression::new(integer(2, 4),
+                                                               "*".to_owned(),
+                                                               integer(2, 8),
+                                                               span(4, 9))),
+                        Expression::Infix(InfixExpression::new(integer(3, 11),
+                                                               "+".to_owned(),
+                                                               integer(3, 15),
+                                                               span(11, 16)))];
+    let expected = Expression::Array(ArrayLiteral::new(elements, span(0, 17)));
+    assert_eq!(vec![expression_statement(expected, 17)], program.statements);
+
+    assert_eq!("[]", parse("[]").to_string());
+    assert_eq!("[[1], []]", parse("[[1], []]").to_string());
+}
+
+#[test]
+fn test_hash_literal() {
+    let program = parse(r#"{"one": 1, 2: true}"#);
+
+    let pairs = vec![(Expression::StringLiteral(StringLiteral::new("one".to_owned(), span(1, 6))),
+                      integer(1, 8)),
+                     (integer(2, 11), Expression::Boolean(Boolean::new(true, span(14, 18))))];
+    let expected = Expression::Hash(HashLiteral::new(pairs, span(0, 19)));
+    assert_eq!(vec![expression_statement(expected, 19)], program.statements);
+
+    let tests = vec![("{}", "{}"),
+                     (r#"{"one": 0 + 1, "two": 10 - 8}"#, r#"{"one": (0 + 1), "two": (10 - 8)}"#),
+                     ("{a: {b: [1]}}", "{a: {b: [1]}}"),
+                     ("let h = {1: 2}; h[1]", "let h = {1: 2};(h[1])"),
+                     ("if (x) { {1: 2} }", "if x { {1: 2} }"),
+                     ("fn() { return {}; }", "fn() { return {}; }")];
+
+    for (input, expected) in tests {
+        assert_eq!(expected, parse(input).to_string());
     }
 }
+
+#[test]
+fn test_index_expression() {
+    let index = Expression::Infix(InfixExpression::new(integer(1, 8),
+                                                       "+".to_owned(),
+                                                       integer(1, 12),
+                                                       span(8, 13)));
+    let expected = Expression::Index(IndexExpression::new(identifier("myArray", 0),
+                                                          index,
+                                                          span(0, 14)));
+    assert_eq!(vec![expression_statement(expected, 14)],
+               parse("myArray[1 + 1]").statements);
+
+    let tests = vec![("a * [1, 2, 3, 4][b * c] * d", "((a * ([1, 2, 3, 4][(b * c)])) * d)"),
+                     ("add(a * b[2], b[1], 2 * [1, 2][1])",
+                      "add((a * (b[2])), (b[1]), (2 * ([1, 2][1])))"),
+                     ("a[0][1]", "((a[0])[1])"),
+                     ("-a[0]", "(-(a[0]))"),
+                     ("f(x)[0]", "(f(x)[0])"),
+                     ("a[0] ** 2", "((a[0]) ** 2)")];
+
+    for (input, expected) in tests {
+        assert_eq!(expected, parse(input).to_string());
+    }
+}
+
+#[test]
+fn test_invalid_expression() {
+    let tests = vec![("let x = ;",
+                      ParseErrorKind::NoPrefixParseFn {
+                          token_type: TokenType::Semicolon,
+                          span: span(8, 9),
+                      }),
+                     ("(1 + 2",
+                      ParseErrorKind::UnexpectedEof {
+                          expected: TokenType::RParen,
+                          span: span(6, 6),
+                      }),
+                     ("if (x) { x",
+                      ParseErrorKind::UnexpectedEof {
+                          expected: TokenType::RBrace,
+                          span: span(10, 10),
+                      }),
+                     ("fn(x, 1) { x }",
+                      ParseErrorKind::UnexpectedToken {
+                          expected: TokenType::Ident,
+                          found: TokenType::Int,
+                          span: span(6, 7),
+                      }),
+                     ("add(1, 2",
+                      ParseErrorKind::UnexpectedEof {
+                          expected: TokenType::RParen,
+                          span: span(8, 8),
+                      }),
+                     ("[1, 2",
+                      ParseErrorKind::UnexpectedEof {
+                          expected: TokenType::RBracket,
+                          span: span(5, 5),
+                      }),
+                     ("{1 2}",
+                      ParseErrorKind::UnexpectedToken {
+                          expected: TokenType::Colon,
+                          found: TokenType::Int,
+                          span: span(3, 4),
+                      }),
+                     ("{1: 2 3: 4}",
+                      ParseErrorKind::UnexpectedToken {
+                          expected: TokenType::Comma,
+                          found: TokenType::Int,
+                          span: span(6, 7),
+                      }),
+                     ("a[1 2]",
+                      ParseErrorKind::UnexpectedToken {
+                          expected: TokenType::RBracket,
+                          found: TokenType::Int,
+                          span: span(4, 5),
+                      }),
+                     ("99999999999999999999",
+                      ParseErrorKind::IntegerOverflow {
+                          literal: "99999999999999999999".to_owned(),
+                          span: span(0, 20),
+                      }),
+                     ("let big = 1 + 9223372036854775808;",
+                      ParseErrorKind::IntegerOverflow {
+                          literal: "9223372036854775808".to_owned(),
+                          span: span(14, 33),
+                      }),
+                     ("0x1_0000_0000_0000_0000",
+                      ParseErrorKind::IntegerOverflow {
+                          literal: "0x1_0000_0000_0000_0000".to_owned(),
+                          span: span(0, 23),
+                      }),
+                     ("0x",
+                      ParseErrorKind::MalformedNumber {
+                          literal: "0x".to_owned(),
+                          span: span(0, 2),
+                      }),
+                     ("let v = 1.2.3;",
+                      ParseErrorKind::MalformedNumber {
+                          literal: "1.2.3".to_owned(),
+                          span: span(8, 13),
+                      }),
+                     ("0b102",
+                      ParseErrorKind::MalformedNumber {
+                          literal: "0b102".to_owned(),
+                          span: span(0, 5),
+                      }),
+                     ("1e",
+                      ParseErrorKind::MalformedNumber {
+                          literal: "1e".to_owned(),
+                          span: span(0, 2),
+                      }),
+                     ("12ab",
+                      ParseErrorKind::MalformedNumber {
+                          literal: "12ab".to_owned(),
+                          span: span(0, 4),
+                      }),
+                     ("1 += 2",
+                      ParseErrorKind::InvalidAssignmentTarget { span: span(0, 1) }),
+                     ("a + b *= 2",
+                      ParseErrorKind::InvalidAssignmentTarget { span: span(0, 5) }),
+                     ("a & b",
+                      ParseErrorKind::NoPrefixParseFn {
+                          token_type: TokenType::Illegal,
+                          span: span(2, 3),
+                      }),
+                     (r#"let s = "abc"#,
+                      ParseErrorKind::UnterminatedString { span: span(8, 9) }),
+                     (r#""abc\""#,
+                      ParseErrorKind::UnterminatedString { span: span(0, 1) }),
+                     (r#""abc\"#,
+                      ParseErrorKind::UnterminatedString { span: span(0, 1) }),
+                     (r#""a\qb""#,
+                      ParseErrorKind::InvalidEscape {
+                          sequence: r"\q".to_owned(),
+                          span: span(2, 4),
+                      }),
+                     (r#""a\u{110000}""#,
+                      ParseErrorKind::InvalidEscape {
+                          sequence: r"\u{110000}".to_owned(),
+                          span: span(2, 12),
+                      }),
+                     (r#""\u{}""#,
+                      ParseErrorKind::InvalidEscape {
+                          sequence: r"\u{}".to_owned(),
+                          span: span(1, 5),
+                      }),
+                     (r#""\u{41""#,
+                      ParseErrorKind::InvalidEscape {
+                          sequence: r"\u{41".to_owned(),
+                          span: span(1, 6),
+                      }),
+                     (r#""\u41""#,
+                      ParseErrorKind::InvalidEscape {
+                          sequence: r"\u".to_owned(),
+                          span: span(1, 3),
+                      })];
+
+    for (input, expected) in tests {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let errors = parser.parser_program().err().unwrap();
+        assert_eq!(vec![ParseError::new(expected)], errors);
+    }
+
+    let nested = format!("{}1{}", "[".repeat(200_000), "]".repeat(200_000));
+    let errors = Parser::new(Lexer::new(&nested)).parser_program().err().unwrap();
+    let depth = MAX_NESTING_DEPTH;
+    let kind = ParseErrorKind::NestingTooDeep {
+        max: depth,
+        span: span(depth, depth + 1),
+    };
+    assert_eq!(ParseError::new(kind), errors[0]);
+
+    let nested = format!("{}1", "-".repeat(MAX_NESTING_DEPTH - 1));
+    assert!(Parser::new(Lexer::new(&nested)).parser_program().is_ok());
+
+    // A flat chain doesn't make the parser recurse, but builds a tree as deep
+    // as it is long.
+    let tests = vec![format!("1{}", " + 1".repeat(100_000)),
+                     format!("f{}", "()".repeat(100_000)),
+                     format!("({}) + 1", "1 + ".repeat(MAX_EXPRESSION_DEPTH / 2) + "1") +
+                     &" + 1".repeat(MAX_EXPRESSION_DEPTH / 2)];
+
+    for input in tests {
+        let errors = Parser::new(Lexer::new(&input)).parser_program().err().unwrap();
+        match *errors[0].kind() {
+            ParseErrorKind::NestingTooDeep { max, .. } => assert_eq!(MAX_EXPRESSION_DEPTH, max),
+            ref other => panic!("wrong error kind. got={:?}", other),
+        }
+    }
+
+    let chain = format!("1{}", " + 1".repeat(MAX_EXPRESSION_DEPTH - 1));
+    assert!(Parser::new(Lexer::new(&chain)).parser_program().is_ok());
+
+    let messages = vec![("let x = ;", "no prefix parse function for Semicolon found"),
+                        ("(1 + 2", "unexpected end of input, expected RParen"),
+                        ("fn(x, 1) { x }", "expected next token to be Ident, got Int instead"),
+                        ("99999999999999999999",
+                         "integer literal 99999999999999999999 is too large for a 64-bit integer"),
+                        ("0x", "malformed number literal 0x"),
+                        (r#""abc"#, "unterminated string literal"),
+                        (r#""\x""#, r"invalid escape sequence \x")];
+
+    for (input, expected) in messages {
+        let lexer = Lexer::new(input);
+        let mut parser = Parser::new(lexer);
+
+        let errors = parser.parser_program().err().unwrap();
+        assert_eq!(expected, errors[0].to_string());
+    }
+}
+
+#[test]
+fn test_comments() {
+    let input = r#"
+    // adds two numbers
+    let add = fn(a, b) { /* a /* nested */ comment */ a + b };
+    add(1, 2) // trailing
+    "#;
+
+    let expected = "let add = fn(a, b) { (a + b) };add(1, 2)";
+    assert_eq!(expected, parse(input).to_string());
+
+    let mut parser = Parser::new(Lexer::new(input).with_comments());
+    assert_eq!(expected, parser.parser_program().unwrap().to_string());
+
+    let tests = vec![("1 + /* 2", span(4, 6)), ("let x /* = 1;", span(6, 8)), ("x /*", span(2, 4))];
+
+    for (input, span_) in tests {
+        let errors = Parser::new(Lexer::new(input)).parser_program().err().unwrap();
+        assert_eq!(vec![ParseError::new(ParseErrorKind::UnterminatedComment { span: span_ })],
+                   errors);
+        assert_eq!("unterminated block comment", errors[0].to_string());
+    }
+}
+
+#[test]
+fn test_error_recovery() {
+    let input = r#"
+    let x = 5;
+    let = 10;
+    let y = * 2;
+    let add = fn(a, b) { a + ; return a + b; };
+    (1 + 2
+    let z = add(x, y);
+    }
+    return z;
+    "#;
+
+    let lexer = Lexer::new(input);
+    let mut parser = Parser::new(lexer);
+
+    let (program, errors) = parser.parse_program_with_errors();
+
+    let expected_errors = vec![("expected next token to be Ident, got Assign instead", 3, 9),
+                               ("no prefix parse function for Asterisk found", 4, 13),
+                               ("no prefix parse function for Semicolon found", 5, 30),
+                               ("expected next token to be RParen, got Let instead", 7, 5),
+                               ("no prefix parse function for RBrace found", 8, 5)];
+    assert_eq!(expected_errors.len(), errors.len());
+    for (err, (message, line, column)) in errors.iter().zip(expected_errors) {
+        assert_eq!(message, err.error_description());
+        assert_eq!((line, column), (err.span().line, err.span().column));
+    }
+
+    let statements = program.statements
+        .iter()
+        .map(|s| s.to_string())
+        .collect::<Vec<String>>();
+    assert_eq!(vec!["let x = 5;",
+                    "let add = fn(a, b) { return (a + b); };",
+                    "let z = add(x, y);",
+                    "return z;"],
+               statements);
+}
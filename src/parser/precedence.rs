@@ -0,0 +1,39 @@
+use token::TokenType;
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Copy)]
+pub enum Precedence {
+    Lowest,
+    Assign,
+    Or,
+    And,
+    Equals,
+    LessGreater,
+    Sum,
+    Product,
+    Prefix,
+    Power,
+    Call,
+    Index,
+}
+
+impl Precedence {
+    pub fn from_token_type(token_type: &TokenType) -> Self {
+        match *token_type {
+            TokenType::PlusAssign | TokenType::MinusAssign | TokenType::AsteriskAssign |
+            TokenType::SlashAssign => Precedence::Assign,
+            TokenType::Or => Precedence::Or,
+            TokenType::And => Precedence::And,
+            TokenType::EQ | TokenType::NotEQ => Precedence::Equals,
+            TokenType::LT | TokenType::GT | TokenType::LTEQ | TokenType::GTEQ => {
+                Precedence::LessGreater
+            }
+            TokenType::Plus | TokenType::Minus => Precedence::Sum,
+            TokenType::Slash | TokenType::Asterisk | TokenType::Percent => Precedence::Product,
+            // Binds tighter than a prefix operator, so `-2 ** 2` is `-(2 ** 2)`.
+            TokenType::Power => Precedence::Power,
+            TokenType::LParen => Precedence::Call,
+            TokenType::LBracket => Precedence::Index,
+            _ => Precedence::Lowest,
+        }
+    }
+}
@@ -1,25 +1,52 @@
-use std::io::{self, Write};
-use lexer::*;
-use token::*;
+use std::cell::RefCell;
+use std::io::{self, IsTerminal, Write};
+use std::rc::Rc;
+use lexer::Lexer;
+use parser::Parser;
+use evaluator::{eval_with_context, Environment, Context, Limits, Object};
+use diagnostics::{Diagnostic, Renderer};
+
+/// Reads and evaluates lines from stdin until it is closed. Each line is
+/// evaluated under `limits`.
+pub fn start(limits: Limits) {
+    let env = Rc::new(RefCell::new(Environment::new()));
+    let renderer = if io::stdout().is_terminal() {
+        Renderer::colored()
+    } else {
+        Renderer::plain()
+    };
 
-pub fn start() {
     loop {
         print!(">> ");
         io::stdout().flush().unwrap();
 
         let mut input = String::new();
 
-        io::stdin()
+        let read = io::stdin()
             .read_line(&mut input)
             .expect("Failed to read line");
+        if read == 0 {
+            println!();
+            return;
+        }
 
-        let mut lexer = Lexer::new(input);
-        loop {
-            let token = lexer.next_token();
-            if token.token_type == TokenType::EOF {
-                break;
+        let lexer = Lexer::new(&input);
+        let mut parser = Parser::new(lexer);
+
+        match parser.parser_program() {
+            Ok(program) => {
+                match eval_with_context(&program, &env, &Context::new(limits)) {
+                    Object::Error(err) => {
+                        print!("{}", renderer.render(&Diagnostic::from(&err), "<stdin>", &input))
+                    }
+                    result => println!("{}", result),
+                }
+            }
+            Err(errors) => {
+                for err in errors {
+                    print!("{}", renderer.render(&Diagnostic::from(&err), "<stdin>", &input));
+                }
             }
-            println!("{:?}", token);
         }
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
 mod token;
 mod token_type;
+mod span;
 
 pub use self::token::Token;
-pub use self::token_type::TokenType;
\ No newline at end of file
+pub use self::token_type::TokenType;
+pub use self::span::Span;
@@ -0,0 +1,33 @@
+use std::fmt;
+
+/// A region of source text. `start` and `end` are byte offsets into the
+/// input, `line` and `column` (both 1-based) locate `start`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize, line: usize, column: usize) -> Self {
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+
+    /// Returns a span starting where `self` starts and ending where `other` ends.
+    pub fn to(&self, other: Span) -> Span {
+        Span { end: other.end, ..*self }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
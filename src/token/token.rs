@@ -1,22 +1,24 @@
 use std::fmt;
-use token::TokenType;
+use token::{TokenType, Span};
 
-#[derive(Debug, Clone)]
-pub struct Token {
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Token<'src> {
     pub token_type: TokenType,
-    pub literal: String,
+    pub literal: &'src str,
+    pub span: Span,
 }
 
-impl Token {
-    pub fn new(token_type: TokenType, literal: String) -> Self {
+impl<'src> Token<'src> {
+    pub fn new(token_type: TokenType, literal: &'src str, span: Span) -> Self {
         Token {
-            token_type: token_type,
-            literal: literal,
+            token_type,
+            literal,
+            span,
         }
     }
 }
 
-impl fmt::Display for Token {
+impl<'src> fmt::Display for Token<'src> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{:?}", self)
     }
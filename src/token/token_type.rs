@@ -1,37 +1,58 @@
 use std::fmt;
 use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub enum TokenType {
     Illegal,
     EOF,
 
+    // Trivia, only emitted by a lexer built `with_comments`
+    LineComment,
+    BlockComment,
+    UnterminatedComment,
+
     // Identifier + literals
     Ident,
     Int,
+    Float,
+    String,
 
     // Operaters
     Assign,
+    PlusAssign,
+    MinusAssign,
+    AsteriskAssign,
+    SlashAssign,
     Plus,
     Minus,
     Bang,
     Asterisk,
     Slash,
+    Percent,
+    Power,
 
     LT,
     GT,
+    LTEQ,
+    GTEQ,
 
     EQ,
     NotEQ,
 
+    And,
+    Or,
+
     // Delimiters
     Comma,
     Semicolon,
+    Colon,
 
     LParen,
     RParen,
     LBrace,
     RBrace,
+    LBracket,
+    RBracket,
 
     // Keywords
     Function,
@@ -44,7 +65,7 @@ pub enum TokenType {
 }
 
 impl TokenType {
-    pub fn lookup_indent(indent: &String) -> Self {
+    pub fn lookup_indent(indent: &str) -> Self {
         let keywords = [TokenType::Function,
                         TokenType::Let,
                         TokenType::If,
@@ -54,7 +75,7 @@ impl TokenType {
                         TokenType::Else];
 
         let token_type = indent.parse::<TokenType>();
-        if let Some(t) = token_type.ok() {
+        if let Ok(t) = token_type {
             if keywords.contains(&t) {
                 return t;
             }
@@ -62,6 +83,11 @@ impl TokenType {
 
         TokenType::Ident
     }
+
+    /// Whether tokens of this type carry no meaning for the parser.
+    pub fn is_trivia(&self) -> bool {
+        matches!(*self, TokenType::LineComment | TokenType::BlockComment)
+    }
 }
 
 impl FromStr for TokenType {
@@ -75,14 +101,27 @@ impl FromStr for TokenType {
             "!" => Ok(TokenType::Bang),
             "*" => Ok(TokenType::Asterisk),
             "/" => Ok(TokenType::Slash),
+            "%" => Ok(TokenType::Percent),
+            "**" => Ok(TokenType::Power),
+            "+=" => Ok(TokenType::PlusAssign),
+            "-=" => Ok(TokenType::MinusAssign),
+            "*=" => Ok(TokenType::AsteriskAssign),
+            "/=" => Ok(TokenType::SlashAssign),
             "<" => Ok(TokenType::LT),
             ">" => Ok(TokenType::GT),
+            "<=" => Ok(TokenType::LTEQ),
+            ">=" => Ok(TokenType::GTEQ),
+            "&&" => Ok(TokenType::And),
+            "||" => Ok(TokenType::Or),
             "," => Ok(TokenType::Comma),
             ";" => Ok(TokenType::Semicolon),
+            ":" => Ok(TokenType::Colon),
             "(" => Ok(TokenType::LParen),
             ")" => Ok(TokenType::RParen),
             "{" => Ok(TokenType::LBrace),
             "}" => Ok(TokenType::RBrace),
+            "[" => Ok(TokenType::LBracket),
+            "]" => Ok(TokenType::RBracket),
             "==" => Ok(TokenType::EQ),
             "!=" => Ok(TokenType::NotEQ),
             "fn" => Ok(TokenType::Function),